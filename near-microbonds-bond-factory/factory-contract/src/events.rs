@@ -1,7 +1,10 @@
 use std::fmt;
 
+use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 
+use crate::{MunicipalityProfile, ProjectProfile, ProjectStatus};
+
 /// Enum that represents the data type of the EventLog.
 /// The enum can either be an NftMint or an NftTransfer.
 #[derive(Serialize, Deserialize, Debug)]
@@ -13,6 +16,11 @@ pub enum EventLogVariant {
     AddMunicipality(Vec<AddMunicipalityLog>),
     AddProject(Vec<AddProjectLog>),
     AddProjectToken(Vec<AddProjectTokenLog>),
+    DeployFailed(Vec<DeployFailedLog>),
+    UpdateMunicipalityProfile(Vec<UpdateMunicipalityProfileLog>),
+    UpdateProjectProfile(Vec<UpdateProjectProfileLog>),
+    SetMunicipalityAdmin(Vec<SetMunicipalityAdminLog>),
+    ChangeProjectStatus(Vec<ChangeProjectStatusLog>),
 }
 
 /// Interface to capture data about an event
@@ -86,4 +94,98 @@ pub struct AddProjectTokenLog {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memo: Option<String>,
-}
\ No newline at end of file
+}
+
+/// An event log to capture a failed deployment of a project's token
+///
+/// Arguments
+/// * `municipality_id`: id of the municipality
+/// * `project_id`: id of the project
+/// * `token_id`: id of the token
+/// * `deployer_id`: account the deposit was refunded to
+/// * `deposit`: refunded deposit in yoctoNEAR
+/// * `reason`: why the deployment failed
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DeployFailedLog {
+    pub municipality_id: String,
+    pub project_id: String,
+    pub token_id: String,
+    pub deployer_id: String,
+    pub deposit: U128,
+    pub reason: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture updating the profile of a municipality
+///
+/// Arguments
+/// * `municipality_id`: id of the municipality
+/// * `profile`: the updated profile
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UpdateMunicipalityProfileLog {
+    pub municipality_id: String,
+    pub profile: MunicipalityProfile,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture updating the profile of a project
+///
+/// Arguments
+/// * `municipality_id`: id of the municipality
+/// * `project_id`: id of the project
+/// * `profile`: the updated profile
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UpdateProjectProfileLog {
+    pub municipality_id: String,
+    pub project_id: String,
+    pub profile: ProjectProfile,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture setting the admin of a municipality
+///
+/// Arguments
+/// * `municipality_id`: id of the municipality
+/// * `admin_id`: the new admin, if any
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SetMunicipalityAdminLog {
+    pub municipality_id: String,
+    pub admin_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture a project moving to another status
+///
+/// Arguments
+/// * `municipality_id`: id of the municipality
+/// * `project_id`: id of the project
+/// * `old_status`: status before the change
+/// * `new_status`: status after the change
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChangeProjectStatusLog {
+    pub municipality_id: String,
+    pub project_id: String,
+    pub old_status: ProjectStatus,
+    pub new_status: ProjectStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
@@ -4,6 +4,8 @@ use crate::*;
 use near_contract_standards::non_fungible_token::metadata::NFTContractMetadata;
 use near_sdk::{Promise, env::attached_deposit, Gas, json_types::Base64VecU8, PromiseResult};
 
+const GAS_FOR_RESOLVE_DEPLOY: Gas = Gas(20_000_000_000_000);
+
 #[derive(Serialize)]
 #[serde(crate = "near_sdk::serde")]
 struct TokenInitArgs {
@@ -19,11 +21,15 @@ impl Contract {
     pub fn add_new_municipality(
         &mut self,
         municipality_id: String,
+        profile: MunicipalityProfileArgs,
         memo: Option<String>,
     ) {
         // Make sure called is owner
         self.assert_owner();
 
+        // Make sure the profile is valid
+        profile.assert_valid();
+
         // Insert the new municipality into the UnorderedSet and make sure it doesn't already exist
         assert!(
             self.municipalities.insert(&municipality_id),
@@ -34,11 +40,29 @@ impl Contract {
         assert!(
             self.municipality_to_projects.insert(
                 &municipality_id, 
-                &UnorderedSet::new(StorageKey::MunicipalityToProjectsInner.try_to_vec().unwrap())
+                &UnorderedSet::new(
+                    StorageKey::MunicipalityToProjectsInner {
+                        // We get a new unique prefix for the collection
+                        municipality_id_hash: hash_id(&municipality_id),
+                    }
+                    .try_to_vec()
+                    .unwrap(),
+                )
             ).is_none(),
             "Municipality already exists"
         );
 
+        // Store the profile of the municipality
+        self.municipality_profiles.insert(&municipality_id, &MunicipalityProfile {
+            municipality_id: municipality_id.clone(),
+            display_name: profile.display_name,
+            jurisdiction: profile.jurisdiction,
+            contact_id: profile.contact_id,
+            reference: profile.reference,
+            reference_hash: profile.reference_hash,
+            created_at: current_timestamp_ms(),
+        });
+
         // contruct the add municipality log
         let add_municipality_log: EventLog = EventLog { 
             version: "1.0.0".to_string(), 
@@ -62,11 +86,21 @@ impl Contract {
         &mut self,
         municipality_id: String,
         project_id: String,
+        profile: ProjectProfileArgs,
         memo: Option<String>,
     ) {
         // Make sure the called is the owner
         self.assert_owner();
 
+        // Make sure the profile is valid
+        profile.assert_valid();
+
+        // Make sure the project id can't be confused with the municipality id in the key of the project
+        assert!(
+            !project_id.contains(PROJECT_DELIMITER),
+            "Project ID cannot contain '{}'", PROJECT_DELIMITER
+        );
+
         // Make sure the municipality exists
         assert!(
             self.municipality_to_projects.contains_key(&municipality_id),
@@ -77,21 +111,45 @@ impl Contract {
         let mut projects = self.municipality_to_projects.get(&municipality_id).unwrap();
         assert!(
             projects.insert(&project_id),
-            "Project already exists in mun"
+            "Project already exists in municipality"
         );
 
         // replace with the new set
         self.municipality_to_projects.insert(&municipality_id, &projects);
 
         // Add project to project_to_tokens and make sure it doesn't already exist
+        let project_key = project_key(&municipality_id, &project_id);
         assert!(
             self.project_to_tokens.insert(
-                &project_id,
-                &UnorderedSet::new(StorageKey::ProjectToTokensInner.try_to_vec().unwrap())
+                &project_key,
+                &UnorderedSet::new(
+                    StorageKey::ProjectToTokensInner {
+                        // We get a new unique prefix for the collection
+                        project_key_hash: hash_id(&project_key),
+                    }
+                    .try_to_vec()
+                    .unwrap(),
+                )
             ).is_none(),
             "Project already exists"
         );
 
+        // Keep track of the municipality the project belongs to
+        self.project_to_municipality.insert(&project_key, &municipality_id);
+
+        // Store the profile of the project. Every project starts as a draft
+        self.project_profiles.insert(&project_key, &ProjectProfile {
+            project_id: project_id.clone(),
+            municipality_id: municipality_id.clone(),
+            name: profile.name,
+            description_reference: profile.description_reference,
+            funding_target: profile.funding_target,
+            currency: profile.currency,
+            status: ProjectStatus::Draft,
+            created_at: current_timestamp_ms(),
+        });
+        self.internal_add_project_to_status(&project_key, ProjectStatus::Draft);
+
         // contruct the add project log
         let add_project_log: EventLog = EventLog { 
             version: "1.0.0".to_string(), 
@@ -132,16 +190,13 @@ impl Contract {
         // Make sure the caller is owner
         self.assert_owner();
 
-        // Make sure the municipality exists
-        assert!(
-            self.municipality_to_projects.contains_key(&municipality_id),
-            "Municipality does not exist"
-        );
+        // Make sure the project exists and belongs to the municipality
+        self.assert_project_in_municipality(&municipality_id, &project_id);
 
-        // Make sure the project exists
+        // Make sure the status of the project allows new tokens
         assert!(
-            !self.municipality_to_projects.get(&municipality_id).unwrap().is_empty(),
-            "Project does not exist"
+            self.project_profiles.get(&project_key(&municipality_id, &project_id)).unwrap().status.allows_deploy(),
+            "Project status does not allow deploying tokens"
         );
 
         // Get the code for the token version if it exists
@@ -176,6 +231,27 @@ impl Contract {
         }).unwrap();
 
 
+        // Make sure the sub-account wasn't used for another token already
+        assert!(
+            self.token_to_project.get(&new_token_account_id).is_none(),
+            "Token account already exists"
+        );
+
+        // Reserve the sub-account so a concurrent deployment to it fails right away instead of asynchronously
+        assert!(
+            self.pending_deployments.get(&new_token_account_id).is_none(),
+            "Token account is already being deployed"
+        );
+
+        // Keep track of the deposit so it can be refunded if the deployment fails
+        self.pending_deployments.insert(&new_token_account_id, &PendingDeployment {
+            token_account_id: new_token_account_id.clone(),
+            municipality_id: municipality_id.clone(),
+            project_id: project_id.clone(),
+            deployer_id: env::predecessor_account_id(),
+            deposit: U128(attached_deposit()),
+        });
+
         // Create the account
         Promise::new(new_token_account_id.clone())
             .create_account()
@@ -184,10 +260,15 @@ impl Contract {
             .function_call("new".to_owned(), init_args, 0, Gas(10u64.pow(12)))
             .then(
                 Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RESOLVE_DEPLOY)
                 .resolve_deploy(municipality_id, project_id, new_token_account_id.to_string(), memo)
             )
     }
 
+    /**
+     * Resolves the deployment of a token contract.
+     * On success the token is recorded for the project, on failure the deposit is refunded to the deployer
+     */
     #[private]
     pub fn resolve_deploy(
         &mut self,
@@ -196,21 +277,62 @@ impl Contract {
         new_token_account_id: String,
         memo: Option<String>
     ) {
+        let token_account_id = AccountId::new_unchecked(new_token_account_id.clone());
+
+        // The deployment is not pending anymore so the sub-account reservation is released
+        let pending_deployment = self.pending_deployments.remove(&token_account_id).expect("Deployment is not pending");
+        assert!(
+            pending_deployment.municipality_id == municipality_id && pending_deployment.project_id == project_id,
+            "Project does not exist in municipality"
+        );
+
         match env::promise_result(0) {
             PromiseResult::NotReady => unreachable!(),
-            PromiseResult::Failed => env::panic_str("Failed to deploy token contract"),
+            PromiseResult::Failed => {
+                // The deposit came back to the factory when the batch failed so we send it back to the deployer
+                Promise::new(pending_deployment.deployer_id.clone()).transfer(pending_deployment.deposit.0);
+
+                // contruct the deploy failed log
+                let deploy_failed_log: EventLog = EventLog { 
+                    version: "1.0.0".to_string(), 
+                    event: EventLogVariant::DeployFailed(vec![DeployFailedLog {
+                        // Municipality Id
+                        municipality_id: municipality_id,
+
+                        // Project Id
+                        project_id: project_id,
+
+                        // Token Id
+                        token_id: new_token_account_id,
+
+                        // Account the deposit was refunded to
+                        deployer_id: pending_deployment.deployer_id.to_string(),
+
+                        // Refunded deposit
+                        deposit: pending_deployment.deposit,
+
+                        // Why the deployment failed
+                        reason: "Failed to create the token account or initialize the token contract".to_string(),
+
+                        // An optional memo to include
+                        memo: memo,
+                    }])
+                };
+
+                // log the serialized json
+                env::log_str(&deploy_failed_log.to_string());
+            },
             PromiseResult::Successful(_) => {
-                // Add token for project and make sure it doesn't already exist
-                let mut tokens = self.project_to_tokens.get(&project_id).unwrap();
-                assert!(
-                    tokens.insert(
-                        &AccountId::new_unchecked(new_token_account_id.clone())
-                    ),
-                    "Token already added for project"
-                );
+                // Add token for project. The account was just created so the token can't be added already
+                let project_key = project_key(&municipality_id, &project_id);
+                let mut tokens = self.project_to_tokens.get(&project_key).unwrap();
+                tokens.insert(&token_account_id);
 
                 // Replace the tokens with the new set
-                self.project_to_tokens.insert(&project_id, &tokens);
+                self.project_to_tokens.insert(&project_key, &tokens);
+
+                // Keep track of the project the token belongs to
+                self.token_to_project.insert(&token_account_id, &project_key);
 
                 // contruct the add project log
                 let add_token_log: EventLog = EventLog { 
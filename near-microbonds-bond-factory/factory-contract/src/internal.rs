@@ -1,5 +1,27 @@
 use crate::*;
 
+// Used to generate a unique prefix in the storage for the collections nested under an id
+pub(crate) fn hash_id(id: &str) -> CryptoHash {
+    // Get the default hash
+    let mut hash = CryptoHash::default();
+    // We hash the id and return it
+    hash.copy_from_slice(&env::sha256(id.as_bytes()));
+    hash
+}
+
+// Separates the municipality id from the project id in the key of a project
+pub(crate) const PROJECT_DELIMITER: char = ':';
+
+// Used to key the maps of a project, since project ids are only unique within their municipality
+pub(crate) fn project_key(municipality_id: &str, project_id: &str) -> String {
+    format!("{municipality_id}{PROJECT_DELIMITER}{project_id}")
+}
+
+// Get the current block timestamp in milliseconds
+pub(crate) fn current_timestamp_ms() -> u64 {
+    env::block_timestamp() / 1_000_000
+}
+
 impl Contract {
     pub(crate) fn assert_owner(&self) {
         assert!(
@@ -7,4 +29,40 @@ impl Contract {
             "Caller not owner"
         );
     }
+
+    // Make sure the municipality exists and the project was added under it
+    pub(crate) fn assert_project_in_municipality(&self, municipality_id: &String, project_id: &String) {
+        assert!(
+            self.municipality_to_projects.contains_key(municipality_id),
+            "Municipality does not exist"
+        );
+        assert!(
+            self.project_to_municipality.contains_key(&project_key(municipality_id, project_id)),
+            "Project does not exist in municipality"
+        );
+    }
+
+    // Add a project to the set of projects with a status
+    pub(crate) fn internal_add_project_to_status(&mut self, project_key: &String, status: ProjectStatus) {
+        let mut projects = self.projects_by_status.get(&status).unwrap_or_else(|| {
+            UnorderedSet::new(
+                StorageKey::ProjectsByStatusInner {
+                    // We get a new unique prefix for the collection
+                    status,
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
+        });
+        projects.insert(project_key);
+        self.projects_by_status.insert(&status, &projects);
+    }
+
+    // Remove a project from the set of projects with a status
+    pub(crate) fn internal_remove_project_from_status(&mut self, project_key: &String, status: ProjectStatus) {
+        if let Some(mut projects) = self.projects_by_status.get(&status) {
+            projects.remove(project_key);
+            self.projects_by_status.insert(&status, &projects);
+        }
+    }
 }
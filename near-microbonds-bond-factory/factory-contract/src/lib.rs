@@ -1,4 +1,4 @@
-use near_sdk::{AccountId, collections::{LookupMap, UnorderedSet, LazyOption}, PanicOnDefault, near_bindgen, BorshStorageKey, env::{self}, CryptoHash};
+use near_sdk::{AccountId, collections::{LookupMap, UnorderedMap, UnorderedSet, LazyOption}, PanicOnDefault, near_bindgen, BorshStorageKey, env::{self}, CryptoHash};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::json_types::{U128};
 use near_sdk::serde::{Deserialize, Serialize};
@@ -16,6 +16,8 @@ mod types;
 mod events;
 mod factory_core;
 mod tokens;
+mod profiles;
+mod lifecycle;
 
 /**
  * Description:
@@ -37,29 +39,59 @@ pub struct Contract {
     // MunicipalityObjectId => ProjectObjectId
     pub municipality_to_projects: LookupMap<String, UnorderedSet<String>>,
 
-    // ProjectId => Set of accountIds for each token
+    // MunicipalityId:ProjectId => Set of accountIds for each token
     pub project_to_tokens: LookupMap<String, UnorderedSet<AccountId>>,
 
+    // MunicipalityId:ProjectId => MunicipalityId the project was added under
+    pub project_to_municipality: LookupMap<String, String>,
+
+    // TokenAccountId => MunicipalityId:ProjectId the token was deployed for
+    pub token_to_project: LookupMap<AccountId, String>,
+
+    // MunicipalityId => profile of the municipality
+    pub municipality_profiles: LookupMap<String, MunicipalityProfile>,
+
+    // MunicipalityId:ProjectId => profile of the project
+    pub project_profiles: LookupMap<String, ProjectProfile>,
+
+    // MunicipalityId => account that can manage the projects of the municipality
+    pub municipality_admins: LookupMap<String, AccountId>,
+
+    // ProjectStatus => Set of MunicipalityId:ProjectId keys with that status
+    pub projects_by_status: LookupMap<ProjectStatus, UnorderedSet<String>>,
+
     // TokenVersions
     pub token_versions: UnorderedSet<String>,
 
     // TokenVersion => hash
     pub token_version_to_code: LookupMap<String, LazyOption<Vec<u8>>>,
+
+    // TokenAccountId => deployment that has not been resolved yet
+    pub pending_deployments: UnorderedMap<AccountId, PendingDeployment>,
 }
 
 #[derive(BorshStorageKey, BorshSerialize)]
 pub enum StorageKey {
     Municipalities,
     MunicipalityToProjects,
-    MunicipalityToProjectsInner,
+    MunicipalityToProjectsInner { municipality_id_hash: CryptoHash },
     ProjectToTokens,
-    ProjectToTokensInner,
+    ProjectToTokensInner { project_key_hash: CryptoHash },
     TokensAsVector,
     ProjectsAsVector,
 
     TokenVersions,
     TokenVersionToCode,
-    TokenVersionToCodeInner
+    TokenVersionToCodeInner,
+
+    PendingDeployments,
+    ProjectToMunicipality,
+    TokenToProject,
+    MunicipalityProfiles,
+    ProjectProfiles,
+    MunicipalityAdmins,
+    ProjectsByStatus,
+    ProjectsByStatusInner { status: ProjectStatus },
 }
 
 #[near_bindgen]
@@ -77,7 +109,14 @@ impl Contract {
             municipality_to_projects: LookupMap::new(StorageKey::MunicipalityToProjects.try_to_vec().unwrap()),
             project_to_tokens: LookupMap::new(StorageKey::ProjectToTokens.try_to_vec().unwrap()),
             token_versions: UnorderedSet::new(StorageKey::TokenVersions.try_to_vec().unwrap()),
-            token_version_to_code: LookupMap::new(StorageKey::TokenVersionToCode.try_to_vec().unwrap())
+            token_version_to_code: LookupMap::new(StorageKey::TokenVersionToCode.try_to_vec().unwrap()),
+            pending_deployments: UnorderedMap::new(StorageKey::PendingDeployments.try_to_vec().unwrap()),
+            project_to_municipality: LookupMap::new(StorageKey::ProjectToMunicipality.try_to_vec().unwrap()),
+            token_to_project: LookupMap::new(StorageKey::TokenToProject.try_to_vec().unwrap()),
+            municipality_profiles: LookupMap::new(StorageKey::MunicipalityProfiles.try_to_vec().unwrap()),
+            project_profiles: LookupMap::new(StorageKey::ProjectProfiles.try_to_vec().unwrap()),
+            municipality_admins: LookupMap::new(StorageKey::MunicipalityAdmins.try_to_vec().unwrap()),
+            projects_by_status: LookupMap::new(StorageKey::ProjectsByStatus.try_to_vec().unwrap()),
         };
 
         // Return the Contract object
@@ -0,0 +1,106 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /**
+     * Sets the account that can manage the projects of a municipality, or removes it if none is given - caller has to be contract owner
+     */
+    pub fn set_municipality_admin(
+        &mut self,
+        municipality_id: String,
+        admin_id: Option<AccountId>,
+        memo: Option<String>,
+    ) {
+        // Make sure the caller is the owner
+        self.assert_owner();
+
+        // Make sure the municipality exists
+        assert!(
+            self.municipality_to_projects.contains_key(&municipality_id),
+            "Municipality does not exist"
+        );
+
+        if let Some(admin_id) = admin_id.as_ref() {
+            self.municipality_admins.insert(&municipality_id, admin_id);
+        } else {
+            self.municipality_admins.remove(&municipality_id);
+        }
+
+        // contruct the set municipality admin log
+        let set_municipality_admin_log: EventLog = EventLog { 
+            version: "1.0.0".to_string(), 
+            event: EventLogVariant::SetMunicipalityAdmin(vec![SetMunicipalityAdminLog {
+                // Municipality Id
+                municipality_id: municipality_id,
+
+                // The new admin, if any
+                admin_id: admin_id.map(|admin_id| admin_id.to_string()),
+
+                // An optional memo to include
+                memo: memo,
+            }])
+        };
+
+        // log the serialized json
+        env::log_str(&set_municipality_admin_log.to_string());
+    }
+
+    /**
+     * Moves a project to the next status of its lifecycle - caller has to be contract owner or the admin of the municipality
+     */
+    pub fn change_project_status(
+        &mut self,
+        municipality_id: String,
+        project_id: String,
+        status: ProjectStatus,
+        memo: Option<String>,
+    ) {
+        // Make sure the project belongs to the municipality
+        self.assert_project_in_municipality(&municipality_id, &project_id);
+
+        // Make sure the caller is the owner or the admin of the municipality
+        let caller_id = env::predecessor_account_id();
+        assert!(
+            caller_id == self.owner_id || self.municipality_admins.get(&municipality_id) == Some(caller_id),
+            "Caller not owner or municipality admin"
+        );
+
+        // Make sure the project can move to the new status
+        let project_key = project_key(&municipality_id, &project_id);
+        let mut project_profile = self.project_profiles.get(&project_key).unwrap();
+        let old_status = project_profile.status;
+        assert!(
+            old_status.can_transition_to(&status),
+            "Project cannot move from {:?} to {:?}", old_status, status
+        );
+
+        project_profile.status = status;
+        self.project_profiles.insert(&project_key, &project_profile);
+
+        // Move the project to the set of its new status
+        self.internal_remove_project_from_status(&project_key, old_status);
+        self.internal_add_project_to_status(&project_key, status);
+
+        // contruct the change project status log
+        let change_project_status_log: EventLog = EventLog { 
+            version: "1.0.0".to_string(), 
+            event: EventLogVariant::ChangeProjectStatus(vec![ChangeProjectStatusLog {
+                // Municipality Id
+                municipality_id: municipality_id,
+
+                // Project Id
+                project_id: project_id,
+
+                // The status before and after the change
+                old_status: old_status,
+                new_status: status,
+
+                // An optional memo to include
+                memo: memo,
+            }])
+        };
+
+        // log the serialized json
+        env::log_str(&change_project_status_log.to_string());
+    }
+}
@@ -0,0 +1,95 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /**
+     * Updates the profile of a municipality - caller has to be contract owner
+     */
+    pub fn update_municipality_profile(
+        &mut self,
+        municipality_id: String,
+        profile: MunicipalityProfileArgs,
+        memo: Option<String>,
+    ) {
+        // Make sure the caller is the owner
+        self.assert_owner();
+
+        // Make sure the municipality exists and the profile is valid
+        let mut municipality_profile = self.municipality_profiles.get(&municipality_id).expect("Municipality does not exist");
+        profile.assert_valid();
+
+        // Replace the details but keep when the municipality was added
+        municipality_profile.display_name = profile.display_name;
+        municipality_profile.jurisdiction = profile.jurisdiction;
+        municipality_profile.contact_id = profile.contact_id;
+        municipality_profile.reference = profile.reference;
+        municipality_profile.reference_hash = profile.reference_hash;
+        self.municipality_profiles.insert(&municipality_id, &municipality_profile);
+
+        // contruct the update municipality profile log
+        let update_municipality_profile_log: EventLog = EventLog { 
+            version: "1.0.0".to_string(), 
+            event: EventLogVariant::UpdateMunicipalityProfile(vec![UpdateMunicipalityProfileLog {
+                // Municipality Id
+                municipality_id: municipality_id,
+
+                // The updated profile
+                profile: municipality_profile,
+
+                // An optional memo to include
+                memo: memo,
+            }])
+        };
+
+        // log the serialized json
+        env::log_str(&update_municipality_profile_log.to_string());
+    }
+
+    /**
+     * Updates the profile of a project - caller has to be contract owner
+     */
+    pub fn update_project_profile(
+        &mut self,
+        municipality_id: String,
+        project_id: String,
+        profile: ProjectProfileArgs,
+        memo: Option<String>,
+    ) {
+        // Make sure the caller is the owner
+        self.assert_owner();
+
+        // Make sure the project belongs to the municipality and the profile is valid
+        self.assert_project_in_municipality(&municipality_id, &project_id);
+        profile.assert_valid();
+
+        // Replace the details but keep the status and when the project was added
+        let project_key = project_key(&municipality_id, &project_id);
+        let mut project_profile = self.project_profiles.get(&project_key).unwrap();
+        project_profile.name = profile.name;
+        project_profile.description_reference = profile.description_reference;
+        project_profile.funding_target = profile.funding_target;
+        project_profile.currency = profile.currency;
+        self.project_profiles.insert(&project_key, &project_profile);
+
+        // contruct the update project profile log
+        let update_project_profile_log: EventLog = EventLog { 
+            version: "1.0.0".to_string(), 
+            event: EventLogVariant::UpdateProjectProfile(vec![UpdateProjectProfileLog {
+                // Municipality Id
+                municipality_id: municipality_id,
+
+                // Project Id
+                project_id: project_id,
+
+                // The updated profile
+                profile: project_profile,
+
+                // An optional memo to include
+                memo: memo,
+            }])
+        };
+
+        // log the serialized json
+        env::log_str(&update_project_profile_log.to_string());
+    }
+}
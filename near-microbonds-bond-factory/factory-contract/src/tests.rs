@@ -1,11 +1,12 @@
 /* unit tests */
 #[cfg(test)]
 use crate::Contract;
-use crate::{JsonProject, JsonMunicipality};
+use crate::{JsonProject, JsonMunicipality, MunicipalityProfileArgs, ProjectProfileArgs, ProjectStatus};
 use near_sdk::json_types::{U128, U64};
 use near_sdk::test_utils::{accounts, VMContextBuilder};
 use near_sdk::testing_env;
-use near_sdk::{env, AccountId};
+use near_sdk::{env, AccountId, PromiseResult, RuntimeFeesConfig, VMConfig};
+use std::collections::HashMap;
 
 fn get_context(predecessor: AccountId) -> VMContextBuilder {
     let mut builder = VMContextBuilder::new();
@@ -27,6 +28,25 @@ fn sample_municipality_data() -> JsonMunicipality {
     }
 }
 
+fn sample_municipality_profile() -> MunicipalityProfileArgs {
+    MunicipalityProfileArgs {
+        display_name: "Test Municipality".to_string(),
+        jurisdiction: "US-CA".to_string(),
+        contact_id: accounts(2),
+        reference: None,
+        reference_hash: None,
+    }
+}
+
+fn sample_project_profile() -> ProjectProfileArgs {
+    ProjectProfileArgs {
+        name: "Test Project".to_string(),
+        description_reference: Some("https://example.com/project.json".to_string()),
+        funding_target: U128(1_000_000),
+        currency: "USD".to_string(),
+    }
+}
+
 #[test]
 #[should_panic(expected = "The contract is not initialized")]
 fn test_default() {
@@ -58,7 +78,7 @@ fn test_add_municipality() {
     
     let municipality_data = sample_municipality_data();
 
-    contract.add_new_municipality(municipality_data.municipality_id.clone(), Some("This is a test memo".to_string()));
+    contract.add_new_municipality(municipality_data.municipality_id.clone(), sample_municipality_profile(), Some("This is a test memo".to_string()));
 
     let projects_for_municipality = contract.view_projects_for_municipality(municipality_data.municipality_id.clone(), None, None);
 
@@ -76,7 +96,7 @@ fn test_add_municipality_non_owner() {
     let mut contract = Contract::new(accounts(0).into());
     
     let municipality_data = sample_municipality_data();
-    contract.add_new_municipality(municipality_data.municipality_id.clone(), Some("This is a test memo".to_string()));
+    contract.add_new_municipality(municipality_data.municipality_id.clone(), sample_municipality_profile(), Some("This is a test memo".to_string()));
 }
 
 #[test]
@@ -91,15 +111,16 @@ fn test_add_project() {
     let municipality_data = sample_municipality_data();
     let project_data = sample_project_data();
 
-    contract.add_new_municipality(municipality_data.municipality_id.clone(), Some("This is a test memo".to_string()));
+    contract.add_new_municipality(municipality_data.municipality_id.clone(), sample_municipality_profile(), Some("This is a test memo".to_string()));
     contract.add_new_project(
         municipality_data.municipality_id.clone(),
         project_data.project_id.clone(),
+        sample_project_profile(),
         Some("This is a test memo".to_string())
     );
 
     let projects_for_municipality = contract.view_projects_for_municipality(municipality_data.municipality_id.clone(), None, None);
-    let tokens_for_projects = contract.view_tokens_for_project(project_data.project_id.clone(), None, None);
+    let tokens_for_projects = contract.view_tokens_for_project(municipality_data.municipality_id.clone(), project_data.project_id.clone(), None, None);
 
     // println!("{:?}", tokens_for_projects); // print tokens for projects (debug)
     // println!("{:?}", projects_for_municipality); // print tokens for projects (debug)
@@ -122,10 +143,388 @@ fn test_add_project_non_owner() {
     let municipality_data = sample_municipality_data();
     let project_data = sample_project_data();
 
-    // contract.add_new_municipality(municipality_data.municipality_id.clone(), Some("This is a test memo".to_string()));
+    // contract.add_new_municipality(municipality_data.municipality_id.clone(), sample_municipality_profile(), Some("This is a test memo".to_string()));
     contract.add_new_project(
         municipality_data.municipality_id.clone(),
         project_data.project_id.clone(),
+        sample_project_profile(),
         Some("This is a test memo".to_string())
     );
-}
\ No newline at end of file
+}
+
+#[test]
+#[should_panic(expected = "Project already exists in municipality")]
+fn test_add_project_twice() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    let mut contract = Contract::new(accounts(0));
+
+    contract.add_new_municipality(sample_municipality_data().municipality_id, sample_municipality_profile(), None);
+    contract.add_new_project(sample_municipality_data().municipality_id, sample_project_data().project_id, sample_project_profile(), None);
+    contract.add_new_project(sample_municipality_data().municipality_id, sample_project_data().project_id, sample_project_profile(), None);
+}
+
+const DEPLOY_DEPOSIT: u128 = 10_000_000_000_000_000_000_000_000;
+
+fn setup_deployment(context: &mut VMContextBuilder) -> Contract {
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    let mut contract = Contract::new(accounts(0));
+
+    let municipality_data = sample_municipality_data();
+    let project_data = sample_project_data();
+    contract.add_new_municipality(municipality_data.municipality_id.clone(), sample_municipality_profile(), None);
+    contract.add_new_project(municipality_data.municipality_id.clone(), project_data.project_id.clone(), sample_project_profile(), None);
+    contract.change_project_status(municipality_data.municipality_id.clone(), project_data.project_id.clone(), ProjectStatus::Approved, None);
+
+    // the code of the token version is read from the input
+    context.context.input = vec![0u8; 100];
+    testing_env!(context.build());
+    contract.add_token_version();
+
+    testing_env!(context.attached_deposit(DEPLOY_DEPOSIT).build());
+    contract.add_new_token_for_project(
+        municipality_data.municipality_id,
+        project_data.project_id,
+        "0".to_string(),
+        "bond".to_string(),
+        "Bond".to_string(),
+        "BOND".to_string(),
+        None,
+        None,
+        None,
+        None,
+        None
+    );
+    contract
+}
+
+fn resolve_deployment(context: &mut VMContextBuilder, contract: &mut Contract, promise_result: PromiseResult) {
+    testing_env!(
+        context
+            .attached_deposit(0)
+            .predecessor_account_id(env::current_account_id())
+            .build(),
+        VMConfig::test(),
+        RuntimeFeesConfig::test(),
+        HashMap::default(),
+        vec![promise_result]
+    );
+    contract.resolve_deploy(
+        sample_municipality_data().municipality_id,
+        sample_project_data().project_id,
+        format!("bond.{}", env::current_account_id()),
+        None
+    );
+}
+
+#[test]
+fn test_deploy_succeeded() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_deployment(&mut context);
+    assert_eq!(contract.pending_deployments.len(), 1);
+
+    resolve_deployment(&mut context, &mut contract, PromiseResult::Successful(vec![]));
+
+    assert_eq!(contract.pending_deployments.len(), 0);
+    let tokens_for_project = contract.view_tokens_for_project(sample_municipality_data().municipality_id, sample_project_data().project_id, None, None);
+    assert_eq!(tokens_for_project, vec![format!("bond.{}", env::current_account_id())]);
+}
+
+#[test]
+fn test_deploy_failed_refunds_deposit() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_deployment(&mut context);
+
+    resolve_deployment(&mut context, &mut contract, PromiseResult::Failed);
+
+    // the deployment isn't pending anymore and no token was added
+    assert_eq!(contract.pending_deployments.len(), 0);
+    assert!(contract.view_tokens_for_project(sample_municipality_data().municipality_id, sample_project_data().project_id, None, None).is_empty());
+
+    // the deposit was refunded to the deployer and the failure was logged
+    let receipts = near_sdk::test_utils::get_created_receipts();
+    assert!(receipts.iter().any(|receipt| receipt.receiver_id == accounts(0)));
+    let logs = near_sdk::test_utils::get_logs();
+    assert!(logs[0].contains("\"event\":\"deploy_failed\""));
+    assert!(logs[0].contains(&DEPLOY_DEPOSIT.to_string()));
+}
+
+#[test]
+fn test_view_pending_deployments() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_deployment(&mut context);
+
+    let pending_deployments = contract.view_pending_deployments(None, None);
+    assert_eq!(pending_deployments.len(), 1);
+    assert_eq!(pending_deployments[0].token_account_id.to_string(), format!("bond.{}", env::current_account_id()));
+    assert_eq!(pending_deployments[0].project_id, sample_project_data().project_id);
+    assert_eq!(pending_deployments[0].deployer_id, accounts(0));
+    assert_eq!(pending_deployments[0].deposit, U128(DEPLOY_DEPOSIT));
+
+    resolve_deployment(&mut context, &mut contract, PromiseResult::Successful(vec![]));
+    assert!(contract.view_pending_deployments(None, None).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "Token account is already being deployed")]
+fn test_deploy_while_pending() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_deployment(&mut context);
+
+    testing_env!(context.attached_deposit(DEPLOY_DEPOSIT).build());
+    contract.add_new_token_for_project(
+        sample_municipality_data().municipality_id,
+        sample_project_data().project_id,
+        "0".to_string(),
+        "bond".to_string(),
+        "Bond".to_string(),
+        "BOND".to_string(),
+        None,
+        None,
+        None,
+        None,
+        None
+    );
+}
+
+#[test]
+fn test_reverse_lookups() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_deployment(&mut context);
+
+    // a second municipality and project don't share the collections of the first ones
+    contract.add_new_municipality("test_municipality_2".to_string(), sample_municipality_profile(), None);
+    contract.add_new_project("test_municipality_2".to_string(), "test_project_2_a".to_string(), sample_project_profile(), None);
+    assert_eq!(contract.view_projects_for_municipality("test_municipality_2".to_string(), None, None), vec!["test_project_2_a".to_string()]);
+    assert_eq!(contract.view_projects_for_municipality(sample_municipality_data().municipality_id, None, None), vec![sample_project_data().project_id]);
+
+    resolve_deployment(&mut context, &mut contract, PromiseResult::Successful(vec![]));
+
+    let token_account_id: AccountId = format!("bond.{}", env::current_account_id()).parse().unwrap();
+    assert_eq!(contract.view_project_for_token(token_account_id), Some(sample_project_data().project_id));
+    assert_eq!(contract.view_project_for_token(accounts(3)), None);
+    let token_account_id: AccountId = format!("bond.{}", env::current_account_id()).parse().unwrap();
+    assert_eq!(contract.view_municipality_for_token(token_account_id), Some(sample_municipality_data().municipality_id));
+    assert_eq!(contract.view_municipality_for_token(accounts(3)), None);
+    assert!(contract.view_tokens_for_project("test_municipality_2".to_string(), "test_project_2_a".to_string(), None, None).is_empty());
+}
+
+#[test]
+fn test_same_project_id_in_two_municipalities() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_deployment(&mut context);
+
+    // a second municipality can use the same project id
+    contract.add_new_municipality("test_municipality_2".to_string(), sample_municipality_profile(), None);
+    contract.add_new_project("test_municipality_2".to_string(), sample_project_data().project_id, sample_project_profile(), None);
+    contract.change_project_status("test_municipality_2".to_string(), sample_project_data().project_id, ProjectStatus::Approved, None);
+
+    resolve_deployment(&mut context, &mut contract, PromiseResult::Successful(vec![]));
+
+    // the token and the status only belong to the project of the first municipality
+    assert_eq!(contract.view_tokens_for_project(sample_municipality_data().municipality_id, sample_project_data().project_id, None, None).len(), 1);
+    assert!(contract.view_tokens_for_project("test_municipality_2".to_string(), sample_project_data().project_id, None, None).is_empty());
+    assert_eq!(
+        contract.view_project_profile("test_municipality_2".to_string(), sample_project_data().project_id).unwrap().municipality_id,
+        "test_municipality_2".to_string()
+    );
+    assert_eq!(contract.view_projects_by_status(ProjectStatus::Approved, None, None).len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Project ID cannot contain ':'")]
+fn test_add_project_with_delimiter() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    let mut contract = Contract::new(accounts(0));
+
+    contract.add_new_municipality(sample_municipality_data().municipality_id, sample_municipality_profile(), None);
+    contract.add_new_project(sample_municipality_data().municipality_id, "test:project".to_string(), sample_project_profile(), None);
+}
+
+#[test]
+#[should_panic(expected = "Project does not exist in municipality")]
+fn test_deploy_for_project_of_other_municipality() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_deployment(&mut context);
+    contract.add_new_municipality("test_municipality_2".to_string(), sample_municipality_profile(), None);
+    contract.add_new_project("test_municipality_2".to_string(), "test_project_2_a".to_string(), sample_project_profile(), None);
+
+    testing_env!(context.attached_deposit(DEPLOY_DEPOSIT).build());
+    contract.add_new_token_for_project(
+        sample_municipality_data().municipality_id,
+        "test_project_2_a".to_string(),
+        "0".to_string(),
+        "bond2".to_string(),
+        "Bond".to_string(),
+        "BOND".to_string(),
+        None,
+        None,
+        None,
+        None,
+        None
+    );
+}
+
+#[test]
+fn test_profiles() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.block_timestamp(1_000_000_000).build());
+    let mut contract = Contract::new(accounts(0));
+
+    let municipality_id = sample_municipality_data().municipality_id;
+    let project_id = sample_project_data().project_id;
+    contract.add_new_municipality(municipality_id.clone(), sample_municipality_profile(), None);
+    contract.add_new_project(municipality_id.clone(), project_id.clone(), sample_project_profile(), None);
+
+    let municipality_profile = contract.view_municipality_profile(municipality_id.clone()).unwrap();
+    assert_eq!(municipality_profile.display_name, "Test Municipality");
+    assert_eq!(municipality_profile.jurisdiction, "US-CA");
+    assert_eq!(municipality_profile.created_at, 1_000);
+
+    let project_profile = contract.view_project_profile(municipality_id.clone(), project_id.clone()).unwrap();
+    assert_eq!(project_profile.municipality_id, municipality_id);
+    assert_eq!(project_profile.funding_target, U128(1_000_000));
+    assert_eq!(project_profile.status, ProjectStatus::Draft);
+
+    // the owner updates the profiles later on
+    testing_env!(context.block_timestamp(2_000_000_000).build());
+    let mut municipality_args = sample_municipality_profile();
+    municipality_args.display_name = "Renamed Municipality".to_string();
+    contract.update_municipality_profile(municipality_id.clone(), municipality_args, None);
+    let mut project_args = sample_project_profile();
+    project_args.funding_target = U128(2_000_000);
+    contract.update_project_profile(municipality_id.clone(), project_id.clone(), project_args, None);
+
+    let municipality_profiles = contract.view_municipality_profiles(None, None);
+    assert_eq!(municipality_profiles.len(), 1);
+    assert_eq!(municipality_profiles[0].display_name, "Renamed Municipality");
+    assert_eq!(municipality_profiles[0].created_at, 1_000);
+
+    let project_profiles = contract.view_project_profiles_for_municipality(municipality_id, None, None);
+    assert_eq!(project_profiles.len(), 1);
+    assert_eq!(project_profiles[0].funding_target, U128(2_000_000));
+    assert_eq!(project_profiles[0].created_at, 1_000);
+    assert!(near_sdk::test_utils::get_logs()[1].contains("\"event\":\"update_project_profile\""));
+}
+
+#[test]
+#[should_panic(expected = "Jurisdiction must be an ISO 3166 code")]
+fn test_add_municipality_invalid_jurisdiction() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new(accounts(0));
+
+    let mut profile = sample_municipality_profile();
+    profile.jurisdiction = "usa".to_string();
+    contract.add_new_municipality(sample_municipality_data().municipality_id, profile, None);
+}
+
+#[test]
+#[should_panic(expected = "Caller not owner")]
+fn test_update_project_profile_non_owner() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_deployment(&mut context);
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.update_project_profile(
+        sample_municipality_data().municipality_id,
+        sample_project_data().project_id,
+        sample_project_profile(),
+        None
+    );
+}
+
+#[test]
+fn test_project_lifecycle() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new(accounts(0));
+
+    let municipality_id = sample_municipality_data().municipality_id;
+    let project_id = sample_project_data().project_id;
+    contract.add_new_municipality(municipality_id.clone(), sample_municipality_profile(), None);
+    contract.add_new_project(municipality_id.clone(), project_id.clone(), sample_project_profile(), None);
+    contract.add_new_project(municipality_id.clone(), "test_project_2".to_string(), sample_project_profile(), None);
+    contract.set_municipality_admin(municipality_id.clone(), Some(accounts(2)), None);
+    assert_eq!(contract.view_municipality_admin(municipality_id.clone()), Some(accounts(2)));
+
+    // the owner approves the project and the municipality admin moves it on from there
+    contract.change_project_status(municipality_id.clone(), project_id.clone(), ProjectStatus::Approved, None);
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.change_project_status(municipality_id.clone(), project_id.clone(), ProjectStatus::Fundraising, None);
+    contract.change_project_status(municipality_id.clone(), project_id.clone(), ProjectStatus::Active, Some("funded".to_string()));
+    assert!(near_sdk::test_utils::get_logs()[1].contains("\"event\":\"change_project_status\""));
+    assert!(near_sdk::test_utils::get_logs()[1].contains("\"old_status\":\"Fundraising\",\"new_status\":\"Active\""));
+
+    let active_projects = contract.view_projects_by_status(ProjectStatus::Active, None, None);
+    assert_eq!(active_projects.len(), 1);
+    assert_eq!(active_projects[0].project_id, project_id);
+    let draft_projects = contract.view_projects_by_status(ProjectStatus::Draft, None, None);
+    assert_eq!(draft_projects.len(), 1);
+    assert_eq!(draft_projects[0].project_id, "test_project_2");
+
+    contract.change_project_status(municipality_id.clone(), project_id.clone(), ProjectStatus::Matured, None);
+    contract.change_project_status(municipality_id.clone(), project_id.clone(), ProjectStatus::Closed, None);
+    assert_eq!(contract.view_project_profile(municipality_id, project_id).unwrap().status, ProjectStatus::Closed);
+    assert!(contract.view_projects_by_status(ProjectStatus::Active, None, None).is_empty());
+    assert_eq!(contract.view_projects_by_status(ProjectStatus::Closed, None, None).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Project cannot move from Draft to Active")]
+fn test_invalid_status_transition() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new(accounts(0));
+
+    let municipality_id = sample_municipality_data().municipality_id;
+    let project_id = sample_project_data().project_id;
+    contract.add_new_municipality(municipality_id.clone(), sample_municipality_profile(), None);
+    contract.add_new_project(municipality_id.clone(), project_id.clone(), sample_project_profile(), None);
+    contract.change_project_status(municipality_id, project_id, ProjectStatus::Active, None);
+}
+
+#[test]
+#[should_panic(expected = "Caller not owner or municipality admin")]
+fn test_change_project_status_non_admin() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new(accounts(0));
+
+    let municipality_id = sample_municipality_data().municipality_id;
+    let project_id = sample_project_data().project_id;
+    contract.add_new_municipality(municipality_id.clone(), sample_municipality_profile(), None);
+    contract.add_new_project(municipality_id.clone(), project_id.clone(), sample_project_profile(), None);
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.change_project_status(municipality_id, project_id, ProjectStatus::Approved, None);
+}
+
+#[test]
+#[should_panic(expected = "Project status does not allow deploying tokens")]
+fn test_deploy_for_closed_project() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_deployment(&mut context);
+    contract.change_project_status(
+        sample_municipality_data().municipality_id,
+        sample_project_data().project_id,
+        ProjectStatus::Closed,
+        None
+    );
+
+    testing_env!(context.attached_deposit(DEPLOY_DEPOSIT).build());
+    contract.add_new_token_for_project(
+        sample_municipality_data().municipality_id,
+        sample_project_data().project_id,
+        "0".to_string(),
+        "bond2".to_string(),
+        "Bond".to_string(),
+        "BOND".to_string(),
+        None,
+        None,
+        None,
+        None,
+        None
+    );
+}
@@ -1,3 +1,5 @@
+use near_sdk::json_types::Base64VecU8;
+
 use crate::*;
 
 /**
@@ -17,4 +19,137 @@ pub struct JsonProject {
 pub struct JsonMunicipality {
     pub municipality_id: String, // The owner of the Municipality
     pub projects: Vec<String> // Vec containing ProjectIds
-}
\ No newline at end of file
+}
+
+// A token deployment that was started but not resolved yet
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingDeployment {
+    pub token_account_id: AccountId, // The sub-account the token is deployed to
+    pub municipality_id: String, // The municipality the token is deployed for
+    pub project_id: String, // The project the token is deployed for
+    pub deployer_id: AccountId, // The account that attached the deposit
+    pub deposit: U128, // The deposit that is refunded if the deployment fails
+}
+
+// The lifecycle status of a project
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProjectStatus {
+    Draft,
+    Approved,
+    Fundraising,
+    Active,
+    Matured,
+    Defaulted,
+    Closed,
+}
+
+// The details of a municipality that are given when it is added or updated
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MunicipalityProfileArgs {
+    pub display_name: String, // The name shown for the municipality
+    pub jurisdiction: String, // ISO 3166 code of the country or subdivision, ex. "US" or "US-CA"
+    pub contact_id: AccountId, // The account to contact about the municipality
+    pub reference: Option<String>, // URL to an off-chain JSON file with more info
+    pub reference_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of JSON from reference field
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MunicipalityProfile {
+    pub municipality_id: String, // The id of the municipality
+    pub display_name: String, // The name shown for the municipality
+    pub jurisdiction: String, // ISO 3166 code of the country or subdivision, ex. "US" or "US-CA"
+    pub contact_id: AccountId, // The account to contact about the municipality
+    pub reference: Option<String>, // URL to an off-chain JSON file with more info
+    pub reference_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of JSON from reference field
+    pub created_at: u64, // When the municipality was added, Unix epoch in milliseconds
+}
+
+// The details of a project that are given when it is added or updated
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProjectProfileArgs {
+    pub name: String, // The name of the project
+    pub description_reference: Option<String>, // URL to an off-chain description of the project
+    pub funding_target: U128, // The amount the project wants to raise, in the smallest unit of the currency
+    pub currency: String, // The currency the funding target is in, ex. "NEAR" or "USD"
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProjectProfile {
+    pub project_id: String, // The id of the project
+    pub municipality_id: String, // The municipality the project was added under
+    pub name: String, // The name of the project
+    pub description_reference: Option<String>, // URL to an off-chain description of the project
+    pub funding_target: U128, // The amount the project wants to raise, in the smallest unit of the currency
+    pub currency: String, // The currency the funding target is in, ex. "NEAR" or "USD"
+    pub status: ProjectStatus, // The lifecycle status of the project
+    pub created_at: u64, // When the project was added, Unix epoch in milliseconds
+}
+
+impl ProjectStatus {
+    /**
+     * Whether a project can move from this status to the next one.
+     * Projects move along Draft -> Approved -> Fundraising -> Active -> Matured/Defaulted -> Closed,
+     * and can be closed before they are active to cancel them
+     */
+    pub(crate) fn can_transition_to(&self, next: &ProjectStatus) -> bool {
+        matches!(
+            (self, next),
+            (ProjectStatus::Draft, ProjectStatus::Approved)
+                | (ProjectStatus::Approved, ProjectStatus::Fundraising)
+                | (ProjectStatus::Fundraising, ProjectStatus::Active)
+                | (ProjectStatus::Active, ProjectStatus::Matured)
+                | (ProjectStatus::Active, ProjectStatus::Defaulted)
+                | (ProjectStatus::Matured, ProjectStatus::Closed)
+                | (ProjectStatus::Defaulted, ProjectStatus::Closed)
+                | (ProjectStatus::Draft, ProjectStatus::Closed)
+                | (ProjectStatus::Approved, ProjectStatus::Closed)
+                | (ProjectStatus::Fundraising, ProjectStatus::Closed)
+        )
+    }
+
+    // Whether new tokens can be deployed for a project with this status
+    pub(crate) fn allows_deploy(&self) -> bool {
+        matches!(self, ProjectStatus::Approved | ProjectStatus::Fundraising | ProjectStatus::Active)
+    }
+}
+
+impl MunicipalityProfileArgs {
+    // Panics if the details are not valid
+    pub(crate) fn assert_valid(&self) {
+        assert!(!self.display_name.trim().is_empty(), "Display name is required");
+
+        // A country code of two capital letters, optionally followed by a subdivision code
+        let (country, subdivision) = match self.jurisdiction.split_once('-') {
+            Some((country, subdivision)) => (country, Some(subdivision)),
+            None => (self.jurisdiction.as_str(), None),
+        };
+        let valid_country = country.len() == 2 && country.chars().all(|c| c.is_ascii_uppercase());
+        let valid_subdivision = subdivision.map_or(true, |subdivision| {
+            (1..=3).contains(&subdivision.len()) && subdivision.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        });
+        assert!(valid_country && valid_subdivision, "Jurisdiction must be an ISO 3166 code");
+
+        assert!(
+            self.reference_hash.is_none() || self.reference.is_some(),
+            "Reference hash requires a reference"
+        );
+    }
+}
+
+impl ProjectProfileArgs {
+    // Panics if the details are not valid
+    pub(crate) fn assert_valid(&self) {
+        assert!(!self.name.trim().is_empty(), "Project name is required");
+        assert!(!self.currency.trim().is_empty(), "Currency is required");
+    }
+}
@@ -8,12 +8,13 @@ impl Contract {
     // Get the token information for a specific project
     pub fn view_tokens_for_project(
         &self,
+        municipality_id: String,
         project_id: String,
         from_index: Option<U128>,
         limit: Option<u64>,
     ) -> Vec<String> {
-        // Get the set of tokens that belong to the project_id
-        let tokens_for_project_set = self.project_to_tokens.get(&project_id);
+        // Get the set of tokens that belong to the project_id of the municipality
+        let tokens_for_project_set = self.project_to_tokens.get(&project_key(&municipality_id, &project_id));
 
         // If there is some set of tokens, we'll set the tokens variable equal to that set, otherwise return empty vector
         let tokens = if let Some(tokens_for_project_set) = tokens_for_project_set {
@@ -41,7 +42,7 @@ impl Contract {
         limit: Option<u64>,
     ) -> Vec<String> {
         // Get the projects that belong to the municipality
-        let projects_for_municipality_set = self.municipality_to_projects.get(&municipality_id); // todo unwrap
+        let projects_for_municipality_set = self.municipality_to_projects.get(&municipality_id);
 
         // If there is some set of projects, we'll set the projects variable equal to that set, otherwise return empty vector
         let projects = if let Some(projects_for_municipality_set) = projects_for_municipality_set {
@@ -76,6 +77,105 @@ impl Contract {
             .collect()
     }
 
+    // Get the profile of a municipality
+    pub fn view_municipality_profile(&self, municipality_id: String) -> Option<MunicipalityProfile> {
+        self.municipality_profiles.get(&municipality_id)
+    }
+
+    // Get the profiles of the municipalities stored in the contract
+    pub fn view_municipality_profiles(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<MunicipalityProfile> {
+        // Starting index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        // iterate through municipalities using iterator
+        self.municipalities.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .filter_map(|municipality_id| self.municipality_profiles.get(&municipality_id))
+            .collect()
+    }
+
+    // Get the profile of a project
+    pub fn view_project_profile(&self, municipality_id: String, project_id: String) -> Option<ProjectProfile> {
+        self.project_profiles.get(&project_key(&municipality_id, &project_id))
+    }
+
+    // Get the profiles of the projects that belong to a municipality
+    pub fn view_project_profiles_for_municipality(
+        &self,
+        municipality_id: String,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<ProjectProfile> {
+        self.view_projects_for_municipality(municipality_id.clone(), from_index, limit)
+            .iter()
+            .filter_map(|project_id| self.project_profiles.get(&project_key(&municipality_id, project_id)))
+            .collect()
+    }
+
+    // Get the profiles of the projects with a status
+    pub fn view_projects_by_status(
+        &self,
+        status: ProjectStatus,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<ProjectProfile> {
+        // Get the set of projects with the status
+        let projects = if let Some(projects) = self.projects_by_status.get(&status) {
+            projects
+        } else {
+            return vec![];
+        };
+
+        // Starting index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        // iterate through the projects using iterator
+        projects.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .filter_map(|project_key| self.project_profiles.get(&project_key))
+            .collect()
+    }
+
+    // Get the account that can manage the projects of a municipality
+    pub fn view_municipality_admin(&self, municipality_id: String) -> Option<AccountId> {
+        self.municipality_admins.get(&municipality_id)
+    }
+
+    // Get the municipality a token was deployed for
+    pub fn view_municipality_for_token(&self, token_account_id: AccountId) -> Option<String> {
+        self.token_to_project.get(&token_account_id)
+            .and_then(|project_key| self.project_to_municipality.get(&project_key))
+    }
+
+    // Get the project a token was deployed for
+    pub fn view_project_for_token(&self, token_account_id: AccountId) -> Option<String> {
+        self.token_to_project.get(&token_account_id)
+            .and_then(|project_key| self.project_profiles.get(&project_key))
+            .map(|project_profile| project_profile.project_id)
+    }
+
+    // Get the token deployments that were started but not resolved yet
+    pub fn view_pending_deployments(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<PendingDeployment> {
+        // Starting index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        // iterate through pending deployments using iterator
+        self.pending_deployments.values()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
     pub fn owner(
         &self
     ) -> AccountId {
@@ -10,6 +10,18 @@ const test = anyTest as TestFn<{
 const INITIAL_BALANCE = '100000000000000000000000000000'; // 100,000 NEAR / 100,000^24 yoctoNEAR
 const PRICE_PER_BYTE = '10000000000000000000'; // 10,000,000,000,000,000,000 yoctoNEAR
 
+const MUNICIPALITY_PROFILE = {
+    display_name: 'Test Municipality',
+    jurisdiction: 'US-CA',
+    contact_id: 'contact.test.near',
+};
+
+const PROJECT_PROFILE = {
+    name: 'Test Project',
+    funding_target: '1000000',
+    currency: 'USD',
+};
+
 type TokenMetadata = {
     name: string;
     symbol: string;
@@ -109,6 +121,7 @@ test.failing('Should fail to add a new municipality if not owner', async (t) =>
 
     await non_owner.call(factory_contract, 'add_new_municipality', {
         municipality_id: 'test_municipality',
+        profile: MUNICIPALITY_PROFILE,
     });
 });
 
@@ -117,6 +130,7 @@ test('Should add a new municipality if owner', async (t) => {
 
     await owner.call(factory_contract, 'add_new_municipality', {
         municipality_id: 'test_municipality',
+        profile: MUNICIPALITY_PROFILE,
     });
 
     const projects_for_municipality: String[] = await factory_contract.view(
@@ -133,10 +147,12 @@ test.failing('Should fail to add a new municipality if the municipality already
 
     await owner.call(factory_contract, 'add_new_municipality', {
         municipality_id: 'test_municipality',
+        profile: MUNICIPALITY_PROFILE,
     });
 
     await owner.call(factory_contract, 'add_new_municipality', {
         municipality_id: 'test_municipality',
+        profile: MUNICIPALITY_PROFILE,
     });
 });
 
@@ -146,11 +162,13 @@ test.failing('Should fail to add a new project if not owner', async (t) => {
 
     await owner.call(factory_contract, 'add_new_municipality', {
         municipality_id: 'test_municipality',
+        profile: MUNICIPALITY_PROFILE,
     });
 
     await non_owner.call(factory_contract, 'add_new_project', {
         municipality_id: 'test_municipality',
         project_id: 'test_project',
+        profile: PROJECT_PROFILE,
     });
 });
 
@@ -160,6 +178,7 @@ test.failing('Should fail to add a new project if the given municipality does no
     await owner.call(factory_contract, 'add_new_project', {
         municipality_id: 'test_municipality',
         project_id: 'test_project',
+        profile: PROJECT_PROFILE,
     });
 });
 
@@ -168,16 +187,19 @@ test.failing('Should fail to add a new project if the project already exists', a
 
     await owner.call(factory_contract, 'add_new_municipality', {
         municipality_id: 'test_municipality',
+        profile: MUNICIPALITY_PROFILE,
     });
 
     await owner.call(factory_contract, 'add_new_project', {
         municipality_id: 'test_municipality',
         project_id: 'test_project',
+        profile: PROJECT_PROFILE,
     });
 
     await owner.call(factory_contract, 'add_new_project', {
         municipality_id: 'test_municipality',
         project_id: 'test_project',
+        profile: PROJECT_PROFILE,
     });
 });
 
@@ -186,11 +208,13 @@ test('Should add a new project if owner', async (t) => {
 
     await owner.call(factory_contract, 'add_new_municipality', {
         municipality_id: 'test_municipality',
+        profile: MUNICIPALITY_PROFILE,
     });
 
     await owner.call(factory_contract, 'add_new_project', {
         municipality_id: 'test_municipality',
         project_id: 'test_project',
+        profile: PROJECT_PROFILE,
     });
 
     const projects_for_municipality: String[] = await factory_contract.view(
@@ -216,12 +240,14 @@ test.failing('Should fail to add a new token to project if not owner', async (t)
     // Add municipality
     await owner.call(factory_contract, 'add_new_municipality', {
         municipality_id: 'test_municipality',
+        profile: MUNICIPALITY_PROFILE,
     });
 
     // Add project
     await owner.call(factory_contract, 'add_new_project', {
         municipality_id: 'test_municipality',
         project_id: 'test_project',
+        profile: PROJECT_PROFILE,
     });
 
     await non_owner.call(factory_contract, 'add_new_token_for_project', {
@@ -247,6 +273,7 @@ test.failing('Should fail to add a new token if the given project does not exist
     // Add municipality
     await owner.call(factory_contract, 'add_new_municipality', {
         municipality_id: 'test_municipality',
+        profile: MUNICIPALITY_PROFILE,
     });
 
     await non_owner.call(factory_contract, 'add_new_token_for_project', {
@@ -262,12 +289,14 @@ test.failing('Should fail to add a new token if the given token version does not
     // Add municipality
     await owner.call(factory_contract, 'add_new_municipality', {
         municipality_id: 'test_municipality',
+        profile: MUNICIPALITY_PROFILE,
     });
 
     // Add project
     await owner.call(factory_contract, 'add_new_project', {
         municipality_id: 'test_municipality',
         project_id: 'test_project',
+        profile: PROJECT_PROFILE,
     });
 
     await non_owner.call(factory_contract, 'add_new_token_for_project', {
@@ -290,12 +319,21 @@ test('Should add a new token to project if owner', async (t) => {
     // Add municipality
     await owner.call(factory_contract, 'add_new_municipality', {
         municipality_id: 'test_municipality',
+        profile: MUNICIPALITY_PROFILE,
     });
 
     // Add project
     await owner.call(factory_contract, 'add_new_project', {
         municipality_id: 'test_municipality',
         project_id: 'test_project',
+        profile: PROJECT_PROFILE,
+    });
+
+    // Approve project
+    await owner.call(factory_contract, 'change_project_status', {
+        municipality_id: 'test_municipality',
+        project_id: 'test_project',
+        status: 'Approved',
     });
 
     await owner.call(factory_contract, 'add_new_token_for_project', {
@@ -1,10 +1,10 @@
 use crate::*;
-use near_contract_standards::non_fungible_token::{bytes_for_approved_account_id, refund_approved_account_ids_iter, refund_approved_account_ids, refund_deposit};
+use near_contract_standards::non_fungible_token::bytes_for_approved_account_id;
 use near_sdk::{ext_contract, assert_one_yocto};
 
 pub trait NonFungibleTokenCore {
-    //approve an account ID to transfer a token on your behalf
-    fn nft_approve(&mut self, token_id: TokenId, account_id: AccountId, msg: Option<String>);
+    //approve an account ID to transfer a token on your behalf, optionally until the approval expires
+    fn nft_approve(&mut self, token_id: TokenId, account_id: AccountId, msg: Option<String>, expires_at: Option<u64>);
 
     //check if the passed in account has access to approve the token ID
 	fn nft_is_approved(
@@ -36,15 +36,18 @@ trait NonFungibleTokenApprovalsReceiver {
 #[near_bindgen]
 impl NonFungibleTokenCore for Contract {
 
-    //allow a specific account ID to approve a token on your behalf
+    //allow a specific account ID to approve a token on your behalf. If an expiry is passed in, the approval can't be used from then on
     #[payable]
-    fn nft_approve(&mut self, token_id: TokenId, account_id: AccountId, msg: Option<String>) {
+    fn nft_approve(&mut self, token_id: TokenId, account_id: AccountId, msg: Option<String>, expires_at: Option<u64>) {
         /*
          * assert at least one yocto for security reasons - this will cause a redirect to the NEAR wallet.
          * The user needs to attach enough to pay for storage on the contract
          */
         assert_at_least_one_yocto();
 
+        // bonds of a soulbound contract can't be transferred so there is nothing to approve
+        assert!(!self.soulbound, "Bonds of this contract are non-transferable");
+
         // get the token object from the token ID
         let mut token = self.tokens_by_id.get(&token_id).expect("No token");
 
@@ -55,29 +58,59 @@ impl NonFungibleTokenCore for Contract {
             "Predecessor must be token owner"
         );
 
+        // make sure the approval doesn't expire right away
+        if let Some(expires_at) = expires_at {
+            assert!(expires_at > current_timestamp_ms(), "Approval expiry must be in the future");
+        }
+
         // get the next approval ID if we need a new approval
         let approval_id: u64 = token.next_approval_id;
 
         // check if the account has been approved already for this token
-        let is_new_approval = token.approved_account_ids
+        let previous_approval_id = token.approved_account_ids
             // insert returns none if the key was not present
-            .insert(account_id.clone(), approval_id)
-            .is_none();
+            .insert(account_id.clone(), approval_id);
 
         // if it was a new approval, we need to calculate how much storage is being used to add the account
-        let storage_used = if is_new_approval {
+        let mut storage_used = if previous_approval_id.is_none() {
             bytes_for_approved_account_id(&account_id)
         } else {
             0
         };
 
+        // replace the expiry of the previous approval with the expiry of the new one, if any
+        let initial_storage_usage = env::storage_usage();
+        if let Some(previous_approval_id) = previous_approval_id {
+            self.approval_expires_at.remove(&(token_id.clone(), previous_approval_id));
+        }
+        if let Some(expires_at) = expires_at {
+            self.approval_expires_at.insert(&(token_id.clone(), approval_id), &expires_at);
+        }
+        storage_used += env::storage_usage().saturating_sub(initial_storage_usage);
+
         // increment the token's next approval ID by 1
         token.next_approval_id += 1;
         // insert the otoken back into the tokens_by_id collection
         self.tokens_by_id.insert(&token_id, &token);
 
-        // refund any excess storage attached by the user. If the user didn't attach enough, panic
-        refund_deposit(storage_used);
+        // take the storage from the prepaid balance of the user, or refund any excess storage attached by the user
+        self.internal_pay_storage(storage_used);
+
+        // construct the approve log
+        let nft_approve_log: EventLog = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftApprove(vec![NftApproveLog {
+                owner_id: token.owner_id.to_string(),
+                token_id: token_id.clone(),
+                account_id: account_id.to_string(),
+                approval_id,
+                expires_at,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&nft_approve_log.to_string());
 
         if let Some(msg) = msg {
             // Defaulting GAS weight to 1, no attached deposit, and no static GAS to attach
@@ -104,8 +137,8 @@ impl NonFungibleTokenCore for Contract {
         // get the approval number for the passed in account ID
         let approval = token.approved_account_ids.get(&approved_account_id);
 
-        // If there was some approval ID found for the account ID
-        if let Some(approval) = approval {
+        // If there was some approval ID found for the account ID that didn't expire
+        if let Some(approval) = approval.filter(|approval| self.internal_is_approval_active(&token_id, **approval)) {
             // if a specific approval_id was passed into the function
             if let Some(approval_id) = approval_id {
                 // return if the approval ID passed in matched the actual approval ID for the account
@@ -114,7 +147,7 @@ impl NonFungibleTokenCore for Contract {
             } else {
                 true
             }
-            // if there was no approval ID found for the account ID, we simple return false
+            // if there was no active approval ID found for the account ID, we simple return false
         } else {
             false
         }
@@ -133,16 +166,18 @@ impl NonFungibleTokenCore for Contract {
         assert_eq!(&predecessor_account_id, &token.owner_id);
 
         // if the account ID was in the token's approval, we remove it and if statement logic executes
-        if token
+        if let Some(approval_id) = token
             .approved_account_ids
             .remove(&account_id)
-            .is_some()
         {
             // refund the fund released by removing the approved_account_id to the caller of the function
-            refund_approved_account_ids_iter(predecessor_account_id, [account_id].iter());
+            self.internal_refund_approvals(&token_id, predecessor_account_id, &HashMap::from([(account_id.clone(), approval_id)]));
 
             // insert the token back into the tokens_by_id collection with the account_id removed from the approval list
             self.tokens_by_id.insert(&token_id, &token);
+
+            // log the revoked approval
+            self.internal_log_revoke(&token, token_id, vec![account_id]);
         }
     }
 
@@ -161,11 +196,73 @@ impl NonFungibleTokenCore for Contract {
         //only revoke if the approved account IDs for the token is not empty
         if !token.approved_account_ids.is_empty() {
             //refund the approved account IDs to the caller of the function
-            refund_approved_account_ids(predecessor_account_id, &token.approved_account_ids);
+            self.internal_refund_approvals(&token_id, predecessor_account_id, &token.approved_account_ids);
             //clear the approved account IDs
-            token.approved_account_ids.clear();
+            let account_ids = token.approved_account_ids.drain().map(|(account_id, _)| account_id).collect();
             //insert the token back into the tokens_by_id collection with the approved account IDs cleared
             self.tokens_by_id.insert(&token_id, &token);
+
+            // log the revoked approvals
+            self.internal_log_revoke(&token, token_id, account_ids);
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /*
+        Remove the expired approvals of a token and refund the storage they used to the token owner.
+        Anyone can call this since it only removes approvals that can't be used anymore.
+    */
+    pub fn nft_prune_expired_approvals(&mut self, token_id: TokenId) -> u32 {
+        let mut token = self.tokens_by_id.get(&token_id).expect("No token");
+
+        // get the approvals that expired
+        let expired: HashMap<AccountId, u64> = token.approved_account_ids.iter()
+            .filter(|(_, approval_id)| !self.internal_is_approval_active(&token_id, **approval_id))
+            .map(|(account_id, approval_id)| (account_id.clone(), *approval_id))
+            .collect();
+        if expired.is_empty() {
+            return 0;
+        }
+
+        // remove them from the token and refund the storage to the owner
+        for account_id in expired.keys() {
+            token.approved_account_ids.remove(account_id);
         }
+        self.tokens_by_id.insert(&token_id, &token);
+        self.internal_refund_approvals(&token_id, token.owner_id.clone(), &expired);
+
+        // log the revoked approvals
+        self.internal_log_revoke(&token, token_id, expired.keys().cloned().collect());
+
+        expired.len() as u32
+    }
+
+    //Query for when the approval of an account for a token expires. Returns None if it doesn't expire or there is no approval
+    pub fn nft_approval_expires_at(&self, token_id: TokenId, account_id: AccountId) -> Option<u64> {
+        let token = self.tokens_by_id.get(&token_id).expect("No token");
+        token.approved_account_ids
+            .get(&account_id)
+            .and_then(|approval_id| self.approval_expires_at.get(&(token_id, *approval_id)))
+    }
+}
+
+impl Contract {
+    // log the approvals of a token that were revoked
+    fn internal_log_revoke(&self, token: &Token, token_id: TokenId, account_ids: Vec<AccountId>) {
+        // construct the revoke log
+        let nft_revoke_log: EventLog = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftRevoke(vec![NftRevokeLog {
+                owner_id: token.owner_id.to_string(),
+                token_id,
+                account_ids: account_ids.iter().map(|account_id| account_id.to_string()).collect(),
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&nft_revoke_log.to_string());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,150 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    // Withdraw the escrowed proceeds of a campaign that reached its soft cap to the treasury - caller has to be contract owner
+    pub fn campaign_withdraw(&mut self, series_id: String) -> U128 {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // make sure the campaign succeeded and wasn't withdrawn yet
+        let mut sale = self.sales.get(&series_id).expect("No sale");
+        match sale.campaign_status(current_timestamp_ms()) {
+            Some(CampaignStatus::Succeeded) => {},
+            Some(CampaignStatus::Funding) => env::panic_str("Campaign is still funding"),
+            Some(CampaignStatus::Failed) => env::panic_str("Campaign did not reach its soft cap"),
+            None => env::panic_str("Sale is not a campaign"),
+        }
+        assert!(!sale.withdrawn, "Campaign proceeds were already withdrawn");
+
+        sale.withdrawn = true;
+        self.sales.insert(&series_id, &sale);
+
+        // construct the campaign withdraw log
+        let campaign_withdraw_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::CampaignWithdraw(vec![CampaignWithdrawLog {
+                series_id,
+                treasury_id: sale.config.treasury_id.to_string(),
+                amount: sale.raised.0.to_string(),
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&campaign_withdraw_log.to_string());
+
+        // send the escrowed proceeds to the treasury
+        Promise::new(sale.config.treasury_id).transfer(sale.raised.0);
+
+        sale.raised
+    }
+
+    /*
+        Claim a refund for a campaign that didn't reach its soft cap - caller has to be an investor in the campaign.
+        The provisional bonds of the investor are burned and they get back what they paid, their unclaimed coupons and the storage released.
+        Bonds that were force transferred away are not burned, and what was paid for them stays in the contribution.
+    */
+    pub fn campaign_refund(&mut self, series_id: String) -> U128 {
+        let investor_id = env::predecessor_account_id();
+
+        // make sure the campaign failed
+        let mut sale = self.sales.get(&series_id).expect("No sale");
+        assert_eq!(
+            sale.campaign_status(current_timestamp_ms()),
+            Some(CampaignStatus::Failed),
+            "Campaign did not fail"
+        );
+
+        let contribution_key = (series_id.clone(), investor_id.clone());
+        let contribution = self.contributions
+            .get(&contribution_key)
+            .expect("No contribution to refund");
+
+        // only the bonds the investor still owns are refunded. The others could have been force transferred
+        let (token_ids, remaining_token_ids): (Vec<TokenId>, Vec<TokenId>) = contribution.token_ids
+            .iter()
+            .cloned()
+            .partition(|token_id| {
+                self.tokens_by_id
+                    .get(token_id)
+                    .map(|token| token.owner_id == investor_id)
+                    .unwrap_or(false)
+            });
+        assert!(!token_ids.is_empty(), "Investor no longer owns the bonds of the contribution");
+
+        // the bonds that are refunded get back their share of what was paid
+        let refund_amount = contribution.amount.0 * token_ids.len() as u128 / contribution.token_ids.len() as u128;
+
+        // remove the contribution so it can only be refunded once, or keep what's left of it for the bonds that were moved
+        if remaining_token_ids.is_empty() {
+            self.contributions.remove(&contribution_key);
+        } else {
+            self.contributions.insert(&contribution_key, &Contribution {
+                amount: U128(contribution.amount.0 - refund_amount),
+                token_ids: remaining_token_ids,
+            });
+        }
+
+        // the refunded bonds and proceeds leave the escrow
+        sale.raised = U128(sale.raised.0 - refund_amount);
+        sale.sold_count -= token_ids.len() as u64;
+        self.sales.insert(&series_id, &sale);
+
+        // measure initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        // burn the provisional bonds the investor still owns, paying out the coupons they can't claim after being burned
+        let mut coupon: Balance = 0;
+        for token_id in token_ids.iter() {
+            coupon += self.internal_settle_coupons(token_id);
+            self.internal_burn(token_id, None, None);
+        }
+
+        // construct the campaign refund log
+        let campaign_refund_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::CampaignRefund(vec![CampaignRefundLog {
+                series_id,
+                investor_id: investor_id.to_string(),
+                token_ids,
+                amount: refund_amount.to_string(),
+                coupon: coupon.to_string(),
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&campaign_refund_log.to_string());
+
+        // calculate the storage that was released by burning the bonds
+        let released_storage = initial_storage_usage.saturating_sub(env::storage_usage());
+        let storage_refund = env::STORAGE_PRICE_PER_BYTE * released_storage as u128;
+
+        // refund what the investor paid plus the coupons and the storage refund
+        let amount = refund_amount + coupon + storage_refund;
+        Promise::new(investor_id).transfer(amount);
+
+        U128(amount)
+    }
+
+    //Query for the status of the campaign of a series. Returns None if the sale has no soft cap
+    pub fn campaign_status(&self, series_id: String) -> Option<CampaignStatus> {
+        self.sales.get(&series_id)
+            .expect("No sale")
+            .campaign_status(current_timestamp_ms())
+    }
+
+    //Query for the total amount raised in the sale of a series
+    pub fn campaign_raised(&self, series_id: String) -> U128 {
+        self.sales.get(&series_id).expect("No sale").raised
+    }
+
+    //Query for the amount an investor contributed to the campaign of a series that can still be refunded
+    pub fn campaign_contribution(&self, series_id: String, account_id: AccountId) -> U128 {
+        self.contributions
+            .get(&(series_id, account_id))
+            .map(|contribution| contribution.amount)
+            .unwrap_or(U128(0))
+    }
+}
@@ -0,0 +1,236 @@
+use crate::*;
+
+const MS_PER_DAY: u64 = 86_400_000;
+
+#[near_bindgen]
+impl Contract {
+    /*
+        Set how the fraction of a year between two dates is counted for every bond - caller has to be contract owner.
+        The convention can only be changed before the first bond is minted.
+    */
+    pub fn set_day_count_convention(&mut self, day_count_convention: DayCountConvention, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // changing the convention would change what the bonds that were minted already pay
+        assert_eq!(self.next_mint_index, 0, "Cannot change the day count convention after the first mint");
+
+        self.day_count_convention = day_count_convention;
+
+        // construct the set day count convention log
+        let set_day_count_convention_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SetDayCountConvention(vec![SetDayCountConventionLog {
+                day_count_convention,
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&set_day_count_convention_log.to_string());
+    }
+
+    //Query for the day count convention used to calculate the coupons of every bond
+    pub fn day_count_convention(&self) -> DayCountConvention {
+        self.day_count_convention
+    }
+
+    //Query for the payments of a bond from issue to maturity using pagination. The principal is paid with the last one
+    pub fn nft_coupon_schedule(
+        &self,
+        token_id: TokenId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<CouponPayment> {
+        let bond_terms = self.bond_terms_by_id.get(&token_id).expect("Token has no bond terms");
+
+        // where to start pagination - if we have a from_index, we'll use that - otherwise we start from 0
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        // only the payments of the page are calculated. The first payment has index 1
+        (start as u64..bond_terms.payment_count())
+            .take(limit.unwrap_or(50) as usize)
+            .map(|index| bond_terms.payment(index + 1, self.day_count_convention))
+            .collect()
+    }
+
+    /*
+        Query for the interest a bond accrued since its last payment date, at the given timestamp in milliseconds.
+        If no timestamp is passed in, the current block timestamp is used.
+    */
+    pub fn nft_accrued_interest(&self, token_id: TokenId, timestamp: Option<u64>) -> U128 {
+        let bond_terms = self.bond_terms_by_id.get(&token_id).expect("Token has no bond terms");
+        let timestamp = timestamp.unwrap_or_else(current_timestamp_ms);
+
+        // nothing accrues before issue or after maturity
+        if timestamp < bond_terms.issue_date || timestamp >= bond_terms.maturity_date {
+            return U128(0);
+        }
+
+        // the interest only accrues since the last payment before the timestamp
+        let period_start = bond_terms.payment_date(bond_terms.payments_until(timestamp));
+        U128(bond_terms.coupon_between(period_start, timestamp, self.day_count_convention))
+    }
+
+    /*
+        Query for the next date a bond pays a coupon or its principal, after the given timestamp in milliseconds.
+        If no timestamp is passed in, the current block timestamp is used. Returns None once the bond has matured.
+    */
+    pub fn nft_next_payment_date(&self, token_id: TokenId, timestamp: Option<u64>) -> Option<u64> {
+        let bond_terms = self.bond_terms_by_id.get(&token_id).expect("Token has no bond terms");
+        let timestamp = timestamp.unwrap_or_else(current_timestamp_ms);
+
+        if timestamp >= bond_terms.maturity_date {
+            return None;
+        }
+        Some(bond_terms.payment_date(bond_terms.payments_until(timestamp) + 1))
+    }
+}
+
+impl BondTerms {
+    /*
+        get the date of a payment of the bond. Coupons are paid every 12 / frequency months after issue and the last payment is at maturity.
+        Payment 0 is the issue date, which is where the first period starts.
+    */
+    pub(crate) fn payment_date(&self, index: u64) -> u64 {
+        if index == 0 {
+            return self.issue_date;
+        }
+        if self.coupon_frequency == 0 {
+            return self.maturity_date;
+        }
+
+        // every date is calculated from the issue date so short months don't shift the dates after them
+        let months_per_period = 12 / self.coupon_frequency as u64;
+        add_months(self.issue_date, (index * months_per_period) as u32).min(self.maturity_date)
+    }
+
+    // get the number of payments of the bond, including the last one at maturity
+    pub(crate) fn payment_count(&self) -> u64 {
+        self.coupon_dates_before(self.maturity_date) + 1
+    }
+
+    // get the number of payments that were made at the timestamp, which is the index of the payment that starts its period
+    pub(crate) fn payments_until(&self, timestamp: u64) -> u64 {
+        self.coupon_dates_before(timestamp.saturating_add(1)).min(self.payment_count())
+    }
+
+    // get the number of coupon dates after issue that are before the timestamp, without looking at maturity
+    fn coupon_dates_before(&self, timestamp: u64) -> u64 {
+        if self.coupon_frequency == 0 || timestamp <= self.issue_date {
+            return 0;
+        }
+        let months_per_period = 12 / self.coupon_frequency as u64;
+
+        // estimate from the calendar months in between. The estimate is off by at most one period because of the day of the month
+        let (issue_year, issue_month, _) = civil_from_days(self.issue_date / MS_PER_DAY);
+        let (year, month, _) = civil_from_days(timestamp / MS_PER_DAY);
+        let months = (year * 12 + month as u64) - (issue_year * 12 + issue_month as u64);
+
+        let mut count = months / months_per_period;
+        while count > 0 && add_months(self.issue_date, (count * months_per_period) as u32) >= timestamp {
+            count -= 1;
+        }
+        while add_months(self.issue_date, ((count + 1) * months_per_period) as u32) < timestamp {
+            count += 1;
+        }
+        count
+    }
+
+    // get a payment of the bond, starting at 1. The principal is paid with the last one
+    pub(crate) fn payment(&self, index: u64, day_count_convention: DayCountConvention) -> CouponPayment {
+        let period_start = self.payment_date(index - 1);
+        let payment_date = self.payment_date(index);
+        CouponPayment {
+            period_start,
+            payment_date,
+            coupon: U128(self.coupon_between(period_start, payment_date, day_count_convention)),
+            principal: U128(if index == self.payment_count() { self.face_value.0 } else { 0 }),
+        }
+    }
+
+    /*
+        get the interest the bond accrues between two timestamps, rounded down to the yoctoNEAR.
+        Only integers are used so the result is the same on and off chain.
+    */
+    pub(crate) fn coupon_between(&self, start: u64, end: u64, day_count_convention: DayCountConvention) -> Balance {
+        let (days, days_per_year) = day_count(start, end, day_count_convention);
+        self.face_value.0
+            .checked_mul(self.coupon_rate as u128)
+            .and_then(|amount| amount.checked_mul(days))
+            .expect("Coupon amount overflows, the face value is too large")
+            / (10_000 * days_per_year)
+    }
+}
+
+// get the number of days between two timestamps and the number of days in a year according to the convention
+pub(crate) fn day_count(start: u64, end: u64, day_count_convention: DayCountConvention) -> (u128, u128) {
+    let actual_days = (end / MS_PER_DAY).saturating_sub(start / MS_PER_DAY) as u128;
+    match day_count_convention {
+        DayCountConvention::Actual365Fixed => (actual_days, 365),
+        DayCountConvention::Actual360 => (actual_days, 360),
+        DayCountConvention::Thirty360 => {
+            let (start_year, start_month, start_day) = civil_from_days(start / MS_PER_DAY);
+            let (end_year, end_month, end_day) = civil_from_days(end / MS_PER_DAY);
+
+            // the 31st counts as the 30th, and so does the end day if the period starts at the end of a month
+            let start_day = start_day.min(30);
+            let end_day = if start_day == 30 { end_day.min(30) } else { end_day };
+
+            let days = 360 * (end_year as i64 - start_year as i64)
+                + 30 * (end_month as i64 - start_month as i64)
+                + (end_day as i64 - start_day as i64);
+            (days.max(0) as u128, 360)
+        }
+    }
+}
+
+// add calendar months to a timestamp, keeping the time of day. The day is capped at the last day of the new month
+pub(crate) fn add_months(timestamp: u64, months: u32) -> u64 {
+    let (year, month, day) = civil_from_days(timestamp / MS_PER_DAY);
+
+    let months_since_year_zero = year * 12 + (month as u64 - 1) + months as u64;
+    let new_year = months_since_year_zero / 12;
+    let new_month = (months_since_year_zero % 12) as u32 + 1;
+    let new_day = day.min(days_in_month(new_year, new_month));
+
+    days_from_civil(new_year, new_month, new_day) * MS_PER_DAY + timestamp % MS_PER_DAY
+}
+
+fn days_in_month(year: u64, month: u32) -> u32 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+// convert the number of days since the Unix epoch to a (year, month, day) date in the Gregorian calendar
+fn civil_from_days(days: u64) -> (u64, u32, u32) {
+    // shift the epoch to 0000-03-01 so leap days are at the end of a year, then split into 400 year eras
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+    let year = era * 400 + year_of_era + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+// convert a (year, month, day) date in the Gregorian calendar to the number of days since the Unix epoch
+fn days_from_civil(year: u64, month: u32, day: u32) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 } as u64;
+    let day_of_year = (153 * shifted_month + 2) / 5 + day as u64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
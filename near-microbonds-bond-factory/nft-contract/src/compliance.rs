@@ -0,0 +1,155 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /*
+        Move a token to a new owner without the owner's approval, ex. to recover it from a lost wallet or for a court order.
+        This bypasses approvals, freezes, lockups and the user registry - caller has to be the owner or a compliance officer.
+    */
+    pub fn nft_force_transfer(
+        &mut self,
+        token_id: TokenId,
+        receiver_id: AccountId,
+        reason_code: String,
+        memo: Option<String>,
+    ) {
+        // make sure the caller is allowed to force transfers
+        self.assert_compliance();
+
+        // every forced transfer has to be accounted for
+        assert!(!reason_code.trim().is_empty(), "Reason code is required");
+
+        let token = self.tokens_by_id.get(&token_id).expect("No token");
+        assert_ne!(
+            &token.owner_id,
+            &receiver_id,
+            "The token owner and receiver should be different"
+        );
+
+        // give the token to the receiver and refund the previous owner for the approvals that were cleared
+        self.internal_set_owner(&token_id, &token, &receiver_id, true);
+
+        // keep track of the transfer in the history of the token
+        self.internal_record_transfer(&token_id, &token.owner_id, &receiver_id, Some(reason_code.clone()));
+
+        let authorized_id = env::predecessor_account_id().to_string();
+
+        // construct the transfer log as per the events standard so indexers keep track of the owner
+        let nft_transfer_log: EventLog = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftTransfer(vec![NftTransferLog {
+                // the compliance officer that transferred the token on behalf of the old owner
+                authorized_id: Some(authorized_id.clone()),
+                // the old owner's account ID
+                old_owner_id: token.owner_id.to_string(),
+                // the account ID of the new owner of the token
+                new_owner_id: receiver_id.to_string(),
+                // a vector containing the token IDs as strings
+                token_ids: vec![token_id.clone()],
+                // an optional memo to include
+                memo: memo.clone(),
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&nft_transfer_log.to_string());
+
+        // construct the force transfer log
+        let force_transfer_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::ForceTransfer(vec![ForceTransferLog {
+                authorized_id,
+                old_owner_id: token.owner_id.to_string(),
+                new_owner_id: receiver_id.to_string(),
+                token_id,
+                reason_code,
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&force_transfer_log.to_string());
+    }
+
+    // Block every transfer from, to or on behalf of an account - caller has to be the owner or a compliance officer
+    pub fn freeze_account(&mut self, account_id: AccountId, memo: Option<String>) {
+        // make sure the caller is allowed to freeze accounts
+        self.assert_compliance();
+
+        assert!(self.frozen_accounts.insert(&account_id), "Account is already frozen");
+        self.internal_log_freeze(true, Some(account_id), None, memo);
+    }
+
+    // Allow transfers from, to and on behalf of an account again - caller has to be the owner or a compliance officer
+    pub fn unfreeze_account(&mut self, account_id: AccountId, memo: Option<String>) {
+        // make sure the caller is allowed to unfreeze accounts
+        self.assert_compliance();
+
+        assert!(self.frozen_accounts.remove(&account_id), "Account is not frozen");
+        self.internal_log_freeze(false, Some(account_id), None, memo);
+    }
+
+    // Block every transfer of a token - caller has to be the owner or a compliance officer
+    pub fn freeze_token(&mut self, token_id: TokenId, memo: Option<String>) {
+        // make sure the caller is allowed to freeze tokens
+        self.assert_compliance();
+
+        assert!(self.tokens_by_id.contains_key(&token_id), "No token");
+        assert!(self.frozen_tokens.insert(&token_id), "Token is already frozen");
+        self.internal_log_freeze(true, None, Some(token_id), memo);
+    }
+
+    // Allow transfers of a token again - caller has to be the owner or a compliance officer
+    pub fn unfreeze_token(&mut self, token_id: TokenId, memo: Option<String>) {
+        // make sure the caller is allowed to unfreeze tokens
+        self.assert_compliance();
+
+        assert!(self.frozen_tokens.remove(&token_id), "Token is not frozen");
+        self.internal_log_freeze(false, None, Some(token_id), memo);
+    }
+
+    //Query for whether an account is frozen
+    pub fn is_account_frozen(&self, account_id: AccountId) -> bool {
+        self.frozen_accounts.contains(&account_id)
+    }
+
+    //Query for whether a token is frozen
+    pub fn is_token_frozen(&self, token_id: TokenId) -> bool {
+        self.frozen_tokens.contains(&token_id)
+    }
+}
+
+impl Contract {
+    // log freezing or unfreezing an account or token
+    fn internal_log_freeze(
+        &self,
+        frozen: bool,
+        account_id: Option<AccountId>,
+        token_id: Option<TokenId>,
+        memo: Option<String>
+    ) {
+        let freeze_log = FreezeLog {
+            account_id: account_id.map(|account_id| account_id.to_string()),
+            token_id,
+            // an optional memo to include
+            memo,
+        };
+
+        // construct the freeze or unfreeze log
+        let log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: if frozen {
+                EventLogVariant::Freeze(vec![freeze_log])
+            } else {
+                EventLogVariant::Unfreeze(vec![freeze_log])
+            },
+        };
+
+        // log the serialized json
+        env::log_str(&log.to_string());
+    }
+}
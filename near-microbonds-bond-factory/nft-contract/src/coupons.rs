@@ -0,0 +1,171 @@
+use near_contract_standards::non_fungible_token::refund_deposit;
+
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    // Deposit a coupon payment that is split equally among all existing tokens - caller has to be contract owner
+    #[payable]
+    pub fn coupon_deposit(&mut self, memo: Option<String>) -> u64 {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // get the number of tokens that are eligible for the coupon
+        let eligible_supply = self.token_metadata_by_id.len();
+        assert!(eligible_supply > 0, "No tokens to pay a coupon to");
+
+        // split the deposit between the eligible tokens
+        let total_deposit = env::attached_deposit();
+        let amount_per_token = total_deposit / eligible_supply as u128;
+        assert!(amount_per_token > 0, "Attached deposit is too small to pay a coupon to every token");
+
+        // the coupon period ID is the index in the vector of coupon periods
+        let period_id = self.coupon_periods.len();
+        let period = CouponPeriod {
+            period_id,
+            total_deposit: U128(amount_per_token * eligible_supply as u128),
+            amount_per_token: U128(amount_per_token),
+            eligible_supply,
+            // every token minted up until now can claim the coupon
+            mint_index_cutoff: self.next_mint_index,
+            deposited_at: current_timestamp_ms(),
+            claimed_count: 0,
+        };
+        self.coupon_periods.push(&period);
+
+        // refund the part of the deposit that could not be split equally
+        let remainder = total_deposit - period.total_deposit.0;
+        if remainder > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(remainder);
+        }
+
+        // construct the coupon deposit log
+        let coupon_deposit_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::CouponDeposit(vec![CouponDepositLog {
+                period_id,
+                total_deposit: period.total_deposit.0.to_string(),
+                amount_per_token: period.amount_per_token.0.to_string(),
+                eligible_supply,
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&coupon_deposit_log.to_string());
+
+        period_id
+    }
+
+    /*
+        Claim the coupon for a token - caller has to be the token owner.
+        If no period ID is passed in, every unclaimed coupon for the token is claimed.
+        The caller needs to attach enough to pay for the storage used to record the claims.
+    */
+    #[payable]
+    pub fn coupon_claim(&mut self, token_id: TokenId, period_id: Option<u64>) -> U128 {
+        // measure initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        // make sure that the caller is the current owner of the token
+        let owner_id = self.tokens_by_id.get(&token_id).expect("No token").owner_id;
+        assert_eq!(
+            env::predecessor_account_id(),
+            owner_id,
+            "Predecessor must be token owner"
+        );
+
+        // get the periods to claim
+        let periods: Vec<CouponPeriod> = if let Some(period_id) = period_id {
+            vec![self.coupon_periods.get(period_id).expect("No coupon period")]
+        } else {
+            self.coupon_periods.iter().collect()
+        };
+
+        // record the claim for every period the token is still owed a coupon for
+        let mut amount: Balance = 0;
+        let mut period_ids = vec![];
+        for mut period in periods {
+            if let Some(coupon) = self.internal_unclaimed_coupon(&token_id, &period) {
+                self.coupon_claims.insert(&(period.period_id, token_id.clone()));
+                period.claimed_count += 1;
+                self.coupon_periods.replace(period.period_id, &period);
+
+                amount += coupon;
+                period_ids.push(period.period_id);
+            }
+        }
+        assert!(amount > 0, "No coupon to claim");
+
+        // construct the coupon claim log
+        let coupon_claim_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::CouponClaim(vec![CouponClaimLog {
+                owner_id: owner_id.to_string(),
+                token_id,
+                period_ids,
+                amount: amount.to_string(),
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&coupon_claim_log.to_string());
+
+        // calculate the required storage which was the used - initial
+        let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
+
+        // refund any excess storage if the user attached too much. panic if they didnt attach enough to cover the required
+        refund_deposit(required_storage_in_bytes);
+
+        // pay out the coupon to the token owner
+        Promise::new(owner_id).transfer(amount);
+
+        U128(amount)
+    }
+
+    //Query for the coupon periods using pagination
+    pub fn coupon_periods(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<CouponPeriod> {
+        // where to start pagination - if we have a from_index, we'll use that - otherwise we start from 0
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.coupon_periods.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    //Query for the coupons a token can still claim
+    pub fn coupon_entitlements_for_token(&self, token_id: TokenId) -> Vec<CouponEntitlement> {
+        self.coupon_periods.iter()
+            .filter_map(|period| {
+                self.internal_unclaimed_coupon(&token_id, &period).map(|amount| CouponEntitlement {
+                    period_id: period.period_id,
+                    amount: U128(amount),
+                })
+            })
+            .collect()
+    }
+
+    //Query for the total amount of unclaimed coupons for all the tokens of an owner
+    pub fn coupon_outstanding_for_owner(&self, account_id: AccountId) -> U128 {
+        // get the set of tokens for the passed in owner
+        let tokens = if let Some(tokens_for_owner_set) = self.tokens_per_owner.get(&account_id) {
+            tokens_for_owner_set
+        } else {
+            return U128(0);
+        };
+
+        // sum up the unclaimed coupons of every token for every period
+        let periods: Vec<CouponPeriod> = self.coupon_periods.iter().collect();
+        U128(tokens.iter()
+            .map(|token_id| {
+                periods.iter()
+                    .filter_map(|period| self.internal_unclaimed_coupon(&token_id, period))
+                    .sum::<Balance>()
+            })
+            .sum())
+    }
+}
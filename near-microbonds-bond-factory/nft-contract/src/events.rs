@@ -1,6 +1,10 @@
+use std::collections::HashMap;
 use std::fmt;
 
 use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+use crate::DayCountConvention;
 
 /// Enum that represents the data type of the EventLog.
 /// The enum can either be an NftMint or an NftTransfer.
@@ -12,6 +16,39 @@ use near_sdk::serde::{Deserialize, Serialize};
 pub enum EventLogVariant {
     NftMint(Vec<NftMintLog>),
     NftTransfer(Vec<NftTransferLog>),
+    NftBurn(Vec<NftBurnLog>),
+    NftApprove(Vec<NftApproveLog>),
+    NftRevoke(Vec<NftRevokeLog>),
+    AddMinter(Vec<AddMinterLog>),
+    RemoveMinter(Vec<RemoveMinterLog>),
+    CouponDeposit(Vec<CouponDepositLog>),
+    CouponClaim(Vec<CouponClaimLog>),
+    PrincipalDeposit(Vec<PrincipalDepositLog>),
+    BondRedeem(Vec<BondRedeemLog>),
+    CreateSeries(Vec<CreateSeriesLog>),
+    LockSeries(Vec<LockSeriesLog>),
+    UnlockSeries(Vec<UnlockSeriesLog>),
+    AddTransferLockup(Vec<AddTransferLockupLog>),
+    RemoveTransferLockup(Vec<RemoveTransferLockupLog>),
+    SetTransferRegistry(Vec<SetTransferRegistryLog>),
+    ConfigureSale(Vec<ConfigureSaleLog>),
+    EndSale(Vec<EndSaleLog>),
+    BondPurchase(Vec<BondPurchaseLog>),
+    CampaignWithdraw(Vec<CampaignWithdrawLog>),
+    CampaignRefund(Vec<CampaignRefundLog>),
+    SetDefaultRoyalty(Vec<SetDefaultRoyaltyLog>),
+    UpdateRoyalty(Vec<UpdateRoyaltyLog>),
+    SetPlatformFee(Vec<SetPlatformFeeLog>),
+    AddComplianceOfficer(Vec<AddComplianceOfficerLog>),
+    RemoveComplianceOfficer(Vec<RemoveComplianceOfficerLog>),
+    ForceTransfer(Vec<ForceTransferLog>),
+    Freeze(Vec<FreezeLog>),
+    Unfreeze(Vec<FreezeLog>),
+    TakeSnapshot(Vec<TakeSnapshotLog>),
+    SetHistoryCap(Vec<SetHistoryCapLog>),
+    Migrate(Vec<MigrateLog>),
+    SetSoulbound(Vec<SetSoulboundLog>),
+    SetDayCountConvention(Vec<SetDayCountConventionLog>),
 }
 
 /// Interface to capture data about an event
@@ -76,4 +113,538 @@ pub struct NftTransferLog {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memo: Option<String>,
-}
\ No newline at end of file
+}
+
+/// An event log to capture token burning
+///
+/// Arguments
+/// * `owner_id`: owner of tokens to burn
+/// * `authorized_id`: approved account to burn
+/// * `token_ids`: ["1", "abc"]
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftBurnLog {
+    pub owner_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<String>,
+
+    pub token_ids: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture adding a minter
+///
+/// Arguments
+/// * `account_id`: "minter.near"
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AddMinterLog {
+    pub account_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture removing a minter
+///
+/// Arguments
+/// * `account_id`: "minter.near"
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RemoveMinterLog {
+    pub account_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture a coupon deposit
+///
+/// Arguments
+/// * `period_id`: 0
+/// * `total_deposit`: "1000000000000000000000000"
+/// * `amount_per_token`: "10000000000000000000000"
+/// * `eligible_supply`: 100
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CouponDepositLog {
+    pub period_id: u64,
+    pub total_deposit: String,
+    pub amount_per_token: String,
+    pub eligible_supply: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture coupon claims
+///
+/// Arguments
+/// * `owner_id`: "owner.near"
+/// * `token_id`: "1"
+/// * `period_ids`: [0, 1]
+/// * `amount`: "20000000000000000000000"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CouponClaimLog {
+    pub owner_id: String,
+    pub token_id: String,
+    pub period_ids: Vec<u64>,
+    pub amount: String,
+}
+
+/// An event log to capture a principal deposit
+///
+/// Arguments
+/// * `amount`: "1000000000000000000000000"
+/// * `principal_balance`: "5000000000000000000000000"
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PrincipalDepositLog {
+    pub amount: String,
+    pub principal_balance: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture the redemption of a bond
+///
+/// Arguments
+/// * `owner_id`: "owner.near"
+/// * `token_id`: "1"
+/// * `principal`: "1000000000000000000000000"
+/// * `coupon`: "50000000000000000000000"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BondRedeemLog {
+    pub owner_id: String,
+    pub token_id: String,
+    pub principal: String,
+    pub coupon: String,
+}
+
+/// An event log to capture the creation of a bond series
+///
+/// Arguments
+/// * `series_id`: "series-a"
+/// * `max_supply`: 500
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CreateSeriesLog {
+    pub series_id: String,
+    pub max_supply: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture locking the transfers of a series
+///
+/// Arguments
+/// * `series_id`: "series-a"
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LockSeriesLog {
+    pub series_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture unlocking the transfers of a series
+///
+/// Arguments
+/// * `series_id`: "series-a"
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UnlockSeriesLog {
+    pub series_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture adding a transfer lockup
+///
+/// Arguments
+/// * `lockup_id`: 0
+/// * `series_id`: optional series the lockup applies to
+/// * `starts_at`: 1672531200000
+/// * `ends_at`: 1673136000000
+/// * `reason`: optional reason for the lockup
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AddTransferLockupLog {
+    pub lockup_id: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub series_id: Option<String>,
+
+    pub starts_at: u64,
+    pub ends_at: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// An event log to capture removing a transfer lockup
+///
+/// Arguments
+/// * `lockup_id`: 0
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RemoveTransferLockupLog {
+    pub lockup_id: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture binding or unbinding the contract to a user registry
+///
+/// Arguments
+/// * `registry_id`: optional "registry.near", not set when unbinding
+/// * `municipality_id`: optional id of the municipality, not set when unbinding
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SetTransferRegistryLog {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub municipality_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture configuring the primary sale of a series
+///
+/// Arguments
+/// * `series_id`: "series-a"
+/// * `price`: "1000000000000000000000000"
+/// * `starts_at`: 1672531200000
+/// * `ends_at`: 1675209600000
+/// * `max_per_buyer`: optional maximum number of bonds per buyer
+/// * `max_total`: optional maximum number of bonds sold
+/// * `treasury_id`: "treasury.near"
+/// * `soft_cap`: optional minimum amount to raise for the sale to succeed
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ConfigureSaleLog {
+    pub series_id: String,
+    pub price: String,
+    pub starts_at: u64,
+    pub ends_at: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_per_buyer: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_total: Option<u64>,
+
+    pub treasury_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soft_cap: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture ending the primary sale of a series
+///
+/// Arguments
+/// * `series_id`: "series-a"
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EndSaleLog {
+    pub series_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture a purchase in the primary sale
+///
+/// Arguments
+/// * `buyer_id`: "investor.near"
+/// * `series_id`: "series-a"
+/// * `token_ids`: ["series-a:1", "series-a:2"]
+/// * `price`: "2000000000000000000000000"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BondPurchaseLog {
+    pub buyer_id: String,
+    pub series_id: String,
+    pub token_ids: Vec<String>,
+    pub price: String,
+}
+
+/// An event log to capture withdrawing the escrowed proceeds of a successful campaign
+///
+/// Arguments
+/// * `series_id`: "series-a"
+/// * `treasury_id`: "treasury.near"
+/// * `amount`: "5000000000000000000000000"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CampaignWithdrawLog {
+    pub series_id: String,
+    pub treasury_id: String,
+    pub amount: String,
+}
+
+/// An event log to capture refunding an investor of a failed campaign
+///
+/// Arguments
+/// * `series_id`: "series-a"
+/// * `investor_id`: "investor.near"
+/// * `token_ids`: ["series-a:1", "series-a:2"]
+/// * `amount`: "2000000000000000000000000"
+/// * `coupon`: "50000000000000000000000"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CampaignRefundLog {
+    pub series_id: String,
+    pub investor_id: String,
+    pub token_ids: Vec<String>,
+    pub amount: String,
+    pub coupon: String,
+}
+
+/// An event log to capture setting the default royalty for future mints
+///
+/// Arguments
+/// * `royalty`: {"issuer.near": 500}
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SetDefaultRoyaltyLog {
+    pub royalty: HashMap<AccountId, u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture updating the royalty of an existing token
+///
+/// Arguments
+/// * `token_id`: "1"
+/// * `royalty`: {"issuer.near": 500}
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UpdateRoyaltyLog {
+    pub token_id: String,
+    pub royalty: HashMap<AccountId, u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture setting the platform fee
+///
+/// Arguments
+/// * `receiver_id`: optional account the fee is paid to, none if the fee was removed
+/// * `fee`: optional fee in basis points
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SetPlatformFeeLog {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receiver_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture approving an account to transfer a token
+///
+/// Arguments
+/// * `owner_id`: "owner.near"
+/// * `token_id`: "1"
+/// * `account_id`: "market.near"
+/// * `approval_id`: 0
+/// * `expires_at`: optional time the approval expires at
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftApproveLog {
+    pub owner_id: String,
+    pub token_id: String,
+    pub account_id: String,
+    pub approval_id: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+/// An event log to capture revoking the approvals of accounts to transfer a token
+///
+/// Arguments
+/// * `owner_id`: "owner.near"
+/// * `token_id`: "1"
+/// * `account_ids`: ["market.near", "escrow.near"]
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftRevokeLog {
+    pub owner_id: String,
+    pub token_id: String,
+    pub account_ids: Vec<String>,
+}
+
+/// An event log to capture adding a compliance officer
+///
+/// Arguments
+/// * `account_id`: "compliance.near"
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AddComplianceOfficerLog {
+    pub account_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture removing a compliance officer
+///
+/// Arguments
+/// * `account_id`: "compliance.near"
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RemoveComplianceOfficerLog {
+    pub account_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture a transfer forced by the issuer
+///
+/// Arguments
+/// * `authorized_id`: "compliance.near"
+/// * `old_owner_id`: "lost-wallet.near"
+/// * `new_owner_id`: "recovered.near"
+/// * `token_id`: "1"
+/// * `reason_code`: "LOST_KEYS"
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ForceTransferLog {
+    pub authorized_id: String,
+    pub old_owner_id: String,
+    pub new_owner_id: String,
+    pub token_id: String,
+    pub reason_code: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture freezing or unfreezing an account or a token
+///
+/// Arguments
+/// * `account_id`: optional account that was (un)frozen
+/// * `token_id`: optional token that was (un)frozen
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FreezeLog {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture taking a snapshot of the ownership of every token
+///
+/// Arguments
+/// * `snapshot_id`: 0
+/// * `taken_at`: 1672531200000
+/// * `block_height`: 81234567
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TakeSnapshotLog {
+    pub snapshot_id: u64,
+    pub taken_at: u64,
+    pub block_height: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture setting the maximum number of history entries kept per token
+///
+/// Arguments
+/// * `history_cap`: 100
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SetHistoryCapLog {
+    pub history_cap: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture the migration of the contract state after an upgrade
+///
+/// Arguments
+/// * `from_version`: 1
+/// * `to_version`: 1
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MigrateLog {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+/// An event log to capture changing whether the bonds of the contract can be transferred
+///
+/// Arguments
+/// * `soulbound`: true
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SetSoulboundLog {
+    pub soulbound: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture changing how the fraction of a year between two dates is counted
+///
+/// Arguments
+/// * `day_count_convention`: "Thirty360"
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SetDayCountConventionLog {
+    pub day_count_convention: DayCountConvention,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
@@ -0,0 +1,111 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /*
+        Set the maximum number of history entries kept per token - caller has to be contract owner.
+        The oldest entries of a token are removed on its next transfer once it has more than that.
+    */
+    pub fn set_history_cap(&mut self, history_cap: u64, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        self.history_cap = history_cap;
+
+        // construct the set history cap log
+        let set_history_cap_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SetHistoryCap(vec![SetHistoryCapLog {
+                history_cap,
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&set_history_cap_log.to_string());
+    }
+
+    //Query for the maximum number of history entries kept per token
+    pub fn history_cap(&self) -> u64 {
+        self.history_cap
+    }
+
+    //Query for the ownership history of a token using pagination, starting from the oldest entry that was kept
+    pub fn nft_token_history(
+        &self,
+        token_id: TokenId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<TransferRecord> {
+        // get the indices of the oldest and the next history entry
+        let (first, next) = self.token_history_bounds.get(&token_id).unwrap_or((0, 0));
+
+        // where to start pagination - if we have a from_index, we'll use that - otherwise we start from 0
+        let start = first + u128::from(from_index.unwrap_or(U128(0))) as u64;
+
+        (start..next)
+            .take(limit.unwrap_or(50) as usize)
+            .filter_map(|index| self.token_history.get(&(token_id.clone(), index)))
+            .collect()
+    }
+
+    //Query for the number of history entries kept for a token
+    pub fn nft_token_history_length(&self, token_id: TokenId) -> u64 {
+        let (first, next) = self.token_history_bounds.get(&token_id).unwrap_or((0, 0));
+        next - first
+    }
+}
+
+impl Contract {
+    // append a transfer to the history of a token and remove the oldest entries above the cap
+    pub(crate) fn internal_record_transfer(
+        &mut self,
+        token_id: &TokenId,
+        from_id: &AccountId,
+        to_id: &AccountId,
+        memo: Option<String>,
+    ) {
+        let (mut first, next) = self.token_history_bounds.get(token_id).unwrap_or((0, 0));
+
+        // the contract pays for the history, so only the start of a long memo is kept
+        let memo = memo.map(|mut memo| {
+            if memo.len() > MAX_HISTORY_MEMO_LENGTH {
+                let mut length = MAX_HISTORY_MEMO_LENGTH;
+                while !memo.is_char_boundary(length) {
+                    length -= 1;
+                }
+                memo.truncate(length);
+            }
+            memo
+        });
+
+        let record = TransferRecord {
+            from_id: from_id.clone(),
+            to_id: to_id.clone(),
+            timestamp: current_timestamp_ms(),
+            block_height: env::block_height(),
+            memo,
+        };
+        self.token_history.insert(&(token_id.clone(), next), &record);
+        let next = next + 1;
+
+        // remove the oldest entries so storage stays bounded
+        while next - first > self.history_cap {
+            self.token_history.remove(&(token_id.clone(), first));
+            first += 1;
+        }
+
+        self.token_history_bounds.insert(token_id, &(first, next));
+    }
+
+    // remove the whole history of a token once it is burned
+    pub(crate) fn internal_clear_history(&mut self, token_id: &TokenId) {
+        if let Some((first, next)) = self.token_history_bounds.remove(token_id) {
+            for index in first..next {
+                self.token_history.remove(&(token_id.clone(), index));
+            }
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use crate::*;
 use near_contract_standards::non_fungible_token::{hash_account_id};
+use near_contract_standards::non_fungible_token::bytes_for_approved_account_id;
+use crate::series::SERIES_DELIMITER;
 
 //Assert that the user has attached at least 1 yoctoNEAR (for security reasons and to pay for storage)
 pub(crate) fn assert_at_least_one_yocto() {
@@ -9,17 +11,61 @@ pub(crate) fn assert_at_least_one_yocto() {
     )
 }
 
+//used to generate a unique prefix in the storage for the set of tokens of a series
+pub(crate) fn hash_series_id(series_id: &str) -> CryptoHash {
+    //get the default hash
+    let mut hash = CryptoHash::default();
+    //we hash the series ID and return it
+    hash.copy_from_slice(&env::sha256(series_id.as_bytes()));
+    hash
+}
+
+//get the current block timestamp in milliseconds, matching the timestamps used in the token metadata
+pub(crate) fn current_timestamp_ms() -> u64 {
+    env::block_timestamp() / 1_000_000
+}
+
 pub(crate) fn royalty_to_payout(royalty_percentage: u32, amount_to_pay: Balance) -> U128 {
     U128(royalty_percentage as u128 * amount_to_pay / 10_000u128)
 }
 
 impl Contract {
+    // assert that the caller is the owner of the contract
+    pub(crate) fn assert_owner(&self) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.owner_id,
+            "Caller not owner"
+        );
+    }
+
+    // assert that the caller is either the owner of the contract or a registered minter
+    pub(crate) fn assert_minter(&self) {
+        let predecessor_account_id = env::predecessor_account_id();
+        assert!(
+            predecessor_account_id == self.owner_id || self.minters.contains(&predecessor_account_id),
+            "Caller not minter"
+        );
+    }
+
+    // assert that the caller is the owner of the contract or a compliance officer
+    pub(crate) fn assert_compliance(&self) {
+        let predecessor_account_id = env::predecessor_account_id();
+        assert!(
+            predecessor_account_id == self.owner_id || self.compliance_officers.contains(&predecessor_account_id),
+            "Caller not compliance officer"
+        );
+    }
+
     // add a token to the set of tokens an owner has
     pub(crate) fn internal_add_token_to_owner(
         &mut self,
         account_id: &AccountId,
         token_id: &TokenId,
     ) {
+        // copy the tokens of the account if a snapshot was taken since they last changed
+        self.internal_checkpoint_owner(account_id);
+
         // get the set of tokens for the given account
         let mut tokens_set = self.tokens_per_owner.get(account_id).unwrap_or_else(|| {
             UnorderedSet::new(
@@ -44,6 +90,10 @@ impl Contract {
         account_id: &AccountId,
         token_id: &TokenId
     ) {
+        // copy the owner of the token and the tokens of the account if a snapshot was taken since they last changed
+        self.internal_checkpoint_token(token_id, Some(account_id));
+        self.internal_checkpoint_owner(account_id);
+
         // we get the set of tokens that the owner has
         let mut tokens_set = self
                             .tokens_per_owner
@@ -63,24 +113,26 @@ impl Contract {
         }
     }
 
-    pub(crate) fn internal_transfer(
-        &mut self,
-        sender_id: &AccountId,
-        receiver_id: &AccountId,
+    // assert that the sender is either the owner of the token or an approved account with a matching approval ID
+    pub(crate) fn internal_assert_owner_or_approved(
+        &self,
         token_id: &TokenId,
-        // we introduce an approval ID so that people with that approval ID can transfer the token
-        approval_id: Option<u64>,
-        memo: Option<String>
-    ) -> Token {
-        // get the token object by passing in the token_id
-        let token = self.tokens_by_id.get(token_id).expect("No token");
-
+        token: &Token,
+        sender_id: &AccountId,
+        approval_id: Option<u64>
+    ) {
         // if the sender doesn't equal the owner, we panic
         if sender_id != &token.owner_id {
             // if the token's approved account IDs doesn't contain the sender, we panic
-            if !token.approved_account_ids.contains_key(sender_id) {
+            let sender_approval_id = token.approved_account_ids.get(sender_id);
+            if sender_approval_id.is_none() {
                 env::panic_str("Unauthorized");
             }
+
+            // expired approvals can't be used anymore
+            if !self.internal_is_approval_active(token_id, *sender_approval_id.unwrap()) {
+                env::panic_str("Approval has expired");
+            }
         }
 
         // If they included an approval_id, check if the sender's actual approval_id is the same as the one included
@@ -99,6 +151,33 @@ impl Contract {
                 actual_approval_id, enforced_approval_id
             );
         };
+    }
+
+    // assert that the sender can transfer the token to the receiver and return the token object
+    pub(crate) fn internal_assert_can_transfer(
+        &self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        approval_id: Option<u64>
+    ) -> Token {
+        // get the token object by passing in the token_id
+        let token = self.tokens_by_id.get(token_id).expect("No token");
+
+        // make sure the sender is allowed to move the token
+        self.internal_assert_owner_or_approved(token_id, &token, sender_id, approval_id);
+
+        // make sure the token isn't locked
+        if let Some(restriction) = self.internal_transfer_restriction(token_id) {
+            env::panic_str(&restriction);
+        }
+
+        // frozen accounts can't move tokens on behalf of the owner or receive them
+        for account_id in [sender_id, receiver_id] {
+            if self.frozen_accounts.contains(account_id) {
+                env::panic_str(&format!("Account {} is frozen", account_id));
+            }
+        }
 
         // we make sure that the sender isn't sending the token to themselves
         assert_ne!(
@@ -107,23 +186,28 @@ impl Contract {
             "The token owner and receiver should be different"
         );
 
-        // we remove the token from its current owner's set
-        self.internal_remove_token_from_owner(&token.owner_id, token_id);
-        // we then add the token to the receiver_id's set
-        self.internal_add_token_to_owner(receiver_id, token_id);
+        token
+    }
 
-        // we create a new token struct
-        let new_token = Token {
-            owner_id: receiver_id.clone(),
-            // reset the approval account IDs
-            approved_account_ids: Default::default(),
-            next_approval_id: token.next_approval_id,
-            // we copy over the royalties from the previous token
-            royalty: token.royalty.clone()
-        };
+    pub(crate) fn internal_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        // we introduce an approval ID so that people with that approval ID can transfer the token
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        // whether the previous owner is refunded for the cleared approvals right away
+        refund_approvals: bool
+    ) -> Token {
+        // get the token object by passing in the token_id and make sure it can be transferred
+        let token = self.internal_assert_can_transfer(sender_id, receiver_id, token_id, approval_id);
 
-        // insert that new token into the tokens_by_id, replacing the old entry
-        self.tokens_by_id.insert(token_id, &new_token);
+        // give the token to the receiver
+        self.internal_set_owner(token_id, &token, receiver_id, refund_approvals);
+
+        // keep track of the transfer in the history of the token
+        self.internal_record_transfer(token_id, &token.owner_id, receiver_id, memo.clone());
 
         // if there was some memo attached, we log it
         if let Some(memo) = memo.as_ref() {
@@ -161,4 +245,232 @@ impl Contract {
         // return the previous token object that was transferred
         token
     }
-}
\ No newline at end of file
+
+    // get the coupon amount a token can still claim for a period. Returns None if the token isn't eligible or already claimed
+    pub(crate) fn internal_unclaimed_coupon(
+        &self,
+        token_id: &TokenId,
+        period: &CouponPeriod
+    ) -> Option<Balance> {
+        // tokens minted after the coupon was deposited are not part of the period
+        let mint_index = self.mint_index_by_id.get(token_id)?;
+        if mint_index >= period.mint_index_cutoff {
+            return None;
+        }
+
+        // make sure the coupon wasn't claimed yet for the token
+        if self.coupon_claims.contains(&(period.period_id, token_id.clone())) {
+            return None;
+        }
+
+        Some(period.amount_per_token.0)
+    }
+
+    // mark every coupon a token can still claim as paid out and return the total. Used before burning the token since it can't claim them afterwards
+    pub(crate) fn internal_settle_coupons(&mut self, token_id: &TokenId) -> Balance {
+        let mut amount: Balance = 0;
+        let periods: Vec<CouponPeriod> = self.coupon_periods.iter().collect();
+        for mut period in periods {
+            if let Some(coupon) = self.internal_unclaimed_coupon(token_id, &period) {
+                period.claimed_count += 1;
+                self.coupon_periods.replace(period.period_id, &period);
+                amount += coupon;
+            }
+        }
+        amount
+    }
+
+    // store a new token and everything that belongs to it. Logging and storage payment is left to the caller
+    pub(crate) fn internal_mint(
+        &mut self,
+        token_id: &TokenId,
+        metadata: &TokenMetadata,
+        receiver_id: &AccountId,
+        royalty: HashMap<AccountId, u32>,
+        bond_terms: Option<BondTerms>,
+        series_id: Option<String>
+    ) {
+        // token IDs with the delimiter are reserved for the series, otherwise they could block the next token of a series
+        assert!(
+            series_id.is_some() || !token_id.contains(SERIES_DELIMITER),
+            "Token ID cannot contain '{}'", SERIES_DELIMITER
+        );
+
+        // specify the token struct that contains the owner id
+        let token = Token {
+            // set the owner ID equal to the receiver ID passed into the function
+            owner_id: receiver_id.clone(),
+
+            // we set the approved account IDs to the default value (an empty map)
+            approved_account_ids: Default::default(),
+            // the next approval ID is set to 0,
+            next_approval_id: 0,
+            // the map of perpetual royalties for the token (The owner will get 100% - total perpetual royalties)
+            royalty,
+        };
+
+        // insert the token id and token struct and make sure that the token doesn't exist
+        assert!(
+            self.tokens_by_id.insert(token_id, &token).is_none(),
+            "Token already exists"
+        );
+
+        // the token didn't exist in the snapshots that were taken before it was minted
+        self.internal_checkpoint_token(token_id, None);
+
+        // insert the token id and metadata
+        self.token_metadata_by_id.insert(token_id, metadata);
+
+        // if bond terms were passed in, we make sure they are valid and insert them
+        if let Some(bond_terms) = bond_terms {
+            bond_terms.assert_valid();
+            self.bond_terms_by_id.insert(token_id, &bond_terms);
+        }
+
+        // if the token is part of a series, we keep track of it in both directions
+        if let Some(series_id) = series_id {
+            let mut tokens_set = self.tokens_per_series.get(&series_id).unwrap_or_else(|| {
+                UnorderedSet::new(
+                    StorageKey::TokensPerTypeInner {
+                        // we get a new unique prefix for the collection
+                        token_type_hash: hash_series_id(&series_id),
+                    }
+                    .try_to_vec()
+                    .unwrap(),
+                )
+            });
+            tokens_set.insert(token_id);
+            self.tokens_per_series.insert(&series_id, &tokens_set);
+            self.series_by_token_id.insert(token_id, &series_id);
+        }
+
+        // keep track of the order in which the token was minted
+        self.mint_index_by_id.insert(token_id, &self.next_mint_index);
+        self.next_mint_index += 1;
+
+        // call the internal method for adding the token to the owner
+        self.internal_add_token_to_owner(receiver_id, token_id);
+    }
+
+    // remove a token and everything that is stored for it from the contract
+    pub(crate) fn internal_burn(
+        &mut self,
+        token_id: &TokenId,
+        // the optional authorized account ID that burned the token on behalf of the owner
+        authorized_id: Option<String>,
+        memo: Option<String>
+    ) -> Token {
+        // remove the token object and make sure it exists
+        let token = self.tokens_by_id.remove(token_id).expect("No token");
+
+        // remove the token from its owner's set
+        self.internal_remove_token_from_owner(&token.owner_id, token_id);
+
+        // remove everything else that is stored for the token
+        for approval_id in token.approved_account_ids.values() {
+            self.approval_expires_at.remove(&(token_id.clone(), *approval_id));
+        }
+        self.token_metadata_by_id.remove(token_id);
+        self.bond_terms_by_id.remove(token_id);
+        self.frozen_tokens.remove(token_id);
+        self.mint_index_by_id.remove(token_id);
+        self.internal_clear_history(token_id);
+        self.internal_clear_checkpoints(token_id, &token.owner_id);
+
+        // if the token is part of a series, we remove it from the series' set of tokens
+        if let Some(series_id) = self.series_by_token_id.remove(token_id) {
+            let mut tokens_set = self.tokens_per_series.get(&series_id).expect("Series should have tokens");
+            tokens_set.remove(token_id);
+            self.tokens_per_series.insert(&series_id, &tokens_set);
+        }
+
+        // construct the burn log as per the events standard
+        let nft_burn_log: EventLog = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftBurn(vec![NftBurnLog {
+                // the owner of the burned token
+                owner_id: token.owner_id.to_string(),
+                // the optional authorized account ID to burn the token on behalf of the owner
+                authorized_id,
+                // a vector containing the token IDs as strings
+                token_ids: vec![token_id.to_string()],
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&nft_burn_log.to_string());
+
+        // return the token object that was burned
+        token
+    }
+
+    // check if an approval of a token hasn't expired
+    pub(crate) fn internal_is_approval_active(&self, token_id: &TokenId, approval_id: u64) -> bool {
+        match self.approval_expires_at.get(&(token_id.clone(), approval_id)) {
+            Some(expires_at) => current_timestamp_ms() < expires_at,
+            None => true,
+        }
+    }
+
+    // remove the expiries of approvals that are discarded and refund the storage they used to the passed in account
+    pub(crate) fn internal_refund_approvals(
+        &mut self,
+        token_id: &TokenId,
+        account_id: AccountId,
+        approved_account_ids: &HashMap<AccountId, u64>
+    ) {
+        // measure initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        for approval_id in approved_account_ids.values() {
+            self.approval_expires_at.remove(&(token_id.clone(), *approval_id));
+        }
+
+        // the storage released by the approved account IDs and their expiries
+        let released_storage = initial_storage_usage.saturating_sub(env::storage_usage())
+            + approved_account_ids.keys().map(bytes_for_approved_account_id).sum::<u64>();
+
+        if released_storage > 0 {
+            Promise::new(account_id).transfer(Balance::from(released_storage) * env::storage_byte_cost());
+        }
+    }
+
+    /*
+        give the token to the receiver and clear its approvals. The previous owner is refunded for the approvals right away,
+        unless the refund is left to the caller, ex. because the approvals are restored if a transfer call is reverted.
+        No checks are done so the caller has to make sure it's allowed.
+    */
+    pub(crate) fn internal_set_owner(
+        &mut self,
+        token_id: &TokenId,
+        token: &Token,
+        receiver_id: &AccountId,
+        refund_approvals: bool
+    ) {
+        // we remove the token from its current owner's set
+        self.internal_remove_token_from_owner(&token.owner_id, token_id);
+        // we then add the token to the receiver_id's set
+        self.internal_add_token_to_owner(receiver_id, token_id);
+
+        // we create a new token struct
+        let new_token = Token {
+            owner_id: receiver_id.clone(),
+            // reset the approval account IDs
+            approved_account_ids: Default::default(),
+            next_approval_id: token.next_approval_id,
+            // we copy over the royalties from the previous token
+            royalty: token.royalty.clone()
+        };
+
+        // insert that new token into the tokens_by_id, replacing the old entry
+        self.tokens_by_id.insert(token_id, &new_token);
+
+        // remove the expiries of the cleared approvals and refund the previous owner for their storage
+        if refund_approvals {
+            self.internal_refund_approvals(token_id, token.owner_id.clone(), &token.approved_account_ids);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LazyOption, LookupMap, UnorderedMap, UnorderedSet};
+use near_sdk::collections::{LazyOption, LookupMap, LookupSet, UnorderedMap, UnorderedSet, Vector};
 use near_sdk::json_types::{Base64VecU8, U128};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
@@ -23,11 +23,36 @@ mod mint;
 mod nft_core;
 mod royalty;
 mod events;
+mod roles;
+mod coupons;
+mod redemption;
+mod series;
+mod lockups;
+mod registry;
+mod sale;
+mod campaign;
+mod compliance;
+mod snapshots;
+mod history;
+mod storage;
+mod upgrade;
+mod soulbound;
+mod cashflow;
 
 /// This spec can be treated like a version of the standard.
 pub const NFT_METADATA_SPEC: &str = "1.0.0";
 /// This is the name of the NFT standard we're using
 pub const NFT_STANDARD_NAME: &str = "nep171";
+/// This is the name of the standard used for the bond specific events
+pub const MICROBOND_STANDARD_NAME: &str = "microbond";
+/// This is the version of the bond specific events
+pub const MICROBOND_EVENT_VERSION: &str = "1.0.0";
+/// This is the number of ownership history entries kept per token until the owner changes it
+pub const DEFAULT_HISTORY_CAP: u64 = 100;
+/// This is the maximum number of bytes of a memo that is kept in the ownership history, longer memos are cut off
+pub const MAX_HISTORY_MEMO_LENGTH: usize = 64;
+/// This is the number of latest snapshots that can be queried, the ownership copied for older snapshots is pruned
+pub const MAX_RETAINED_SNAPSHOTS: u64 = 10;
 
 
 #[near_bindgen]
@@ -45,8 +70,104 @@ pub struct Contract {
     // keeps track of the token metadata for a given token ID
     pub token_metadata_by_id: UnorderedMap<TokenId, TokenMetadata>,
 
+    // keeps track of the bond terms for a given token ID
+    pub bond_terms_by_id: LookupMap<TokenId, BondTerms>,
+
     // keeps track of the metadata for the contract
     pub metadata: LazyOption<NFTContractMetadata>,
+
+    // keeps track of the accounts that are allowed to mint tokens besides the owner
+    pub minters: UnorderedSet<AccountId>,
+
+    // keeps track of the order in which tokens were minted. Used to decide which tokens are eligible for a coupon
+    pub mint_index_by_id: LookupMap<TokenId, u64>,
+
+    // the mint index that will be given to the next minted token
+    pub next_mint_index: u64,
+
+    // keeps track of all the coupon periods that were funded by the owner
+    pub coupon_periods: Vector<CouponPeriod>,
+
+    // keeps track of the (period ID, token ID) pairs for which the coupon has been claimed
+    pub coupon_claims: LookupSet<(u64, TokenId)>,
+
+    // the amount of yoctoNEAR deposited by the owner to pay out the principal of matured bonds
+    pub principal_balance: Balance,
+
+    // keeps track of the bond series for a given series ID
+    pub series_by_id: UnorderedMap<String, BondSeries>,
+
+    // keeps track of all the token IDs for a given series
+    pub tokens_per_series: LookupMap<String, UnorderedSet<TokenId>>,
+
+    // keeps track of the series for a given token ID if it was minted into one
+    pub series_by_token_id: LookupMap<TokenId, String>,
+
+    // keeps track of the series for which transfers are locked
+    pub series_locked: UnorderedSet<String>,
+
+    // keeps track of the time based transfer lockups for a given lockup ID
+    pub transfer_lockups: UnorderedMap<u64, TransferLockup>,
+
+    // the lockup ID that will be given to the next transfer lockup
+    pub next_lockup_id: u64,
+
+    // the user registry that receivers of transfers are checked against, if any
+    pub transfer_registry: Option<TransferRegistry>,
+
+    // keeps track of the primary sale for a given series ID
+    pub sales: UnorderedMap<String, Sale>,
+
+    // keeps track of the number of bonds a buyer has bought in the sale of a series
+    pub purchases_per_buyer: LookupMap<(String, AccountId), u64>,
+
+    // keeps track of what an investor contributed to the campaign of a series while the proceeds are in escrow
+    pub contributions: LookupMap<(String, AccountId), Contribution>,
+
+    // the perpetual royalties given to newly minted tokens when none are passed in
+    pub default_royalty: HashMap<AccountId, u32>,
+
+    // the fee the platform receives on every sale that uses a payout, if any
+    pub platform_fee: Option<PlatformFee>,
+
+    // keeps track of when the approvals that expire do so for a given (token ID, approval ID)
+    pub approval_expires_at: LookupMap<(TokenId, u64), u64>,
+
+    // keeps track of the accounts that are allowed to force transfers and freeze tokens and accounts besides the owner
+    pub compliance_officers: UnorderedSet<AccountId>,
+
+    // keeps track of the accounts that can't move tokens until they are unfrozen
+    pub frozen_accounts: UnorderedSet<AccountId>,
+
+    // keeps track of the tokens that can't be moved until they are unfrozen
+    pub frozen_tokens: UnorderedSet<TokenId>,
+
+    // keeps track of all the snapshots that were taken by the owner
+    pub snapshots: Vector<Snapshot>,
+
+    // keeps track of the owner of a token at the snapshots it changed after
+    pub token_checkpoints: LookupMap<TokenId, Vec<TokenCheckpoint>>,
+
+    // keeps track of the tokens of an account at the snapshots they changed after
+    pub owner_checkpoints: LookupMap<AccountId, Vec<OwnerCheckpoint>>,
+
+    // keeps track of the ownership history of a given (token ID, index)
+    pub token_history: LookupMap<(TokenId, u64), TransferRecord>,
+
+    // keeps track of the index of the oldest and the next history entry for a given token ID
+    pub token_history_bounds: LookupMap<TokenId, (u64, u64)>,
+
+    // the maximum number of history entries kept per token
+    pub history_cap: u64,
+
+    // keeps track of the prepaid storage balance of a given account ID
+    pub storage_deposits: LookupMap<AccountId, Balance>,
+
+    // whether the bonds of the contract can never be transferred
+    pub soulbound: bool,
+
+    // how the fraction of a year between two dates is counted when calculating coupons
+    pub day_count_convention: DayCountConvention,
 }
 
 /// Helper structure for keys of the persistent collections.
@@ -60,6 +181,27 @@ pub enum StorageKey {
     TokensPerType,
     TokensPerTypeInner { token_type_hash: CryptoHash },
     TokenTypesLocked,
+    Minters,
+    BondTermsById,
+    MintIndexById,
+    CouponPeriods,
+    CouponClaims,
+    SeriesById,
+    SeriesByTokenId,
+    TransferLockups,
+    Sales,
+    PurchasesPerBuyer,
+    Contributions,
+    ApprovalExpiresAt,
+    ComplianceOfficers,
+    FrozenAccounts,
+    FrozenTokens,
+    Snapshots,
+    TokenCheckpoints,
+    OwnerCheckpoints,
+    TokenHistory,
+    TokenHistoryBounds,
+    StorageDeposits,
 }
 
 #[near_bindgen]
@@ -82,29 +224,65 @@ impl Contract {
                 reference: None,
                 reference_hash: None,
             },
+            None,
         )
     }
 
     /*
         initialization function (can only be called once).
         this initializes the contract with metadata that was passed in and
-        the owner_id. 
+        the owner_id. If soulbound is true, the bonds of the contract can never be transferred.
     */
     #[init]
-    pub fn new(owner_id: AccountId, metadata: NFTContractMetadata) -> Self {
+    pub fn new(owner_id: AccountId, metadata: NFTContractMetadata, soulbound: Option<bool>) -> Self {
         let this = Self {
             tokens_per_owner: LookupMap::new(StorageKey::TokensPerOwner.try_to_vec().unwrap()),
             tokens_by_id: LookupMap::new(StorageKey::TokensById.try_to_vec().unwrap()),
             token_metadata_by_id: UnorderedMap::new(
                 StorageKey::TokenMetadataById.try_to_vec().unwrap()
             ),
+            bond_terms_by_id: LookupMap::new(StorageKey::BondTermsById.try_to_vec().unwrap()),
             owner_id,
             metadata: LazyOption::new(
                 StorageKey::NFTContractMetadata.try_to_vec().unwrap(),
                 Some(&metadata)
             ),
+            minters: UnorderedSet::new(StorageKey::Minters.try_to_vec().unwrap()),
+            mint_index_by_id: LookupMap::new(StorageKey::MintIndexById.try_to_vec().unwrap()),
+            next_mint_index: 0,
+            coupon_periods: Vector::new(StorageKey::CouponPeriods.try_to_vec().unwrap()),
+            coupon_claims: LookupSet::new(StorageKey::CouponClaims.try_to_vec().unwrap()),
+            principal_balance: 0,
+            series_by_id: UnorderedMap::new(StorageKey::SeriesById.try_to_vec().unwrap()),
+            tokens_per_series: LookupMap::new(StorageKey::TokensPerType.try_to_vec().unwrap()),
+            series_by_token_id: LookupMap::new(StorageKey::SeriesByTokenId.try_to_vec().unwrap()),
+            series_locked: UnorderedSet::new(StorageKey::TokenTypesLocked.try_to_vec().unwrap()),
+            transfer_lockups: UnorderedMap::new(StorageKey::TransferLockups.try_to_vec().unwrap()),
+            next_lockup_id: 0,
+            transfer_registry: None,
+            sales: UnorderedMap::new(StorageKey::Sales.try_to_vec().unwrap()),
+            purchases_per_buyer: LookupMap::new(StorageKey::PurchasesPerBuyer.try_to_vec().unwrap()),
+            contributions: LookupMap::new(StorageKey::Contributions.try_to_vec().unwrap()),
+            default_royalty: HashMap::new(),
+            platform_fee: None,
+            approval_expires_at: LookupMap::new(StorageKey::ApprovalExpiresAt.try_to_vec().unwrap()),
+            compliance_officers: UnorderedSet::new(StorageKey::ComplianceOfficers.try_to_vec().unwrap()),
+            frozen_accounts: UnorderedSet::new(StorageKey::FrozenAccounts.try_to_vec().unwrap()),
+            frozen_tokens: UnorderedSet::new(StorageKey::FrozenTokens.try_to_vec().unwrap()),
+            snapshots: Vector::new(StorageKey::Snapshots.try_to_vec().unwrap()),
+            token_checkpoints: LookupMap::new(StorageKey::TokenCheckpoints.try_to_vec().unwrap()),
+            owner_checkpoints: LookupMap::new(StorageKey::OwnerCheckpoints.try_to_vec().unwrap()),
+            token_history: LookupMap::new(StorageKey::TokenHistory.try_to_vec().unwrap()),
+            token_history_bounds: LookupMap::new(StorageKey::TokenHistoryBounds.try_to_vec().unwrap()),
+            history_cap: DEFAULT_HISTORY_CAP,
+            storage_deposits: LookupMap::new(StorageKey::StorageDeposits.try_to_vec().unwrap()),
+            soulbound: soulbound.unwrap_or(false),
+            day_count_convention: DayCountConvention::Thirty360,
         };
 
+        // keep track of the layout of the state so it can be migrated on upgrades
+        upgrade::write_schema_version(upgrade::SCHEMA_VERSION);
+
         // Return the contract object
         this
     }
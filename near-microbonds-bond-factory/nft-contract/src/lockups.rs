@@ -0,0 +1,218 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    // Lock all transfers of tokens in a series until it is unlocked - caller has to be contract owner
+    pub fn lock_series(&mut self, series_id: String, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // make sure the series exists and isn't locked already
+        assert!(self.series_by_id.get(&series_id).is_some(), "No series");
+        assert!(
+            self.series_locked.insert(&series_id),
+            "Series is already locked"
+        );
+
+        // construct the lock series log
+        let lock_series_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::LockSeries(vec![LockSeriesLog {
+                series_id,
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&lock_series_log.to_string());
+    }
+
+    // Allow transfers of tokens in a series again - caller has to be contract owner
+    pub fn unlock_series(&mut self, series_id: String, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // remove the series from the locked series and make sure it was locked
+        assert!(
+            self.series_locked.remove(&series_id),
+            "Series is not locked"
+        );
+
+        // construct the unlock series log
+        let unlock_series_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::UnlockSeries(vec![UnlockSeriesLog {
+                series_id,
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&unlock_series_log.to_string());
+    }
+
+    /*
+        Refuse transfers during a period of time - caller has to be contract owner.
+        If a series ID is passed in, only the tokens of that series are locked, otherwise every token is.
+    */
+    pub fn add_transfer_lockup(
+        &mut self,
+        series_id: Option<String>,
+        starts_at: u64,
+        ends_at: u64,
+        reason: Option<String>,
+    ) -> u64 {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // make sure the lockup is valid
+        assert!(ends_at > starts_at, "Lockup must end after it starts");
+        if let Some(series_id) = series_id.as_ref() {
+            assert!(self.series_by_id.get(series_id).is_some(), "No series");
+        }
+
+        let lockup_id = self.next_lockup_id;
+        self.next_lockup_id += 1;
+
+        let lockup = TransferLockup {
+            lockup_id,
+            series_id,
+            starts_at,
+            ends_at,
+            reason,
+        };
+        self.transfer_lockups.insert(&lockup_id, &lockup);
+
+        // construct the add transfer lockup log
+        let add_transfer_lockup_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::AddTransferLockup(vec![AddTransferLockupLog {
+                lockup_id,
+                series_id: lockup.series_id,
+                starts_at,
+                ends_at,
+                reason: lockup.reason,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&add_transfer_lockup_log.to_string());
+
+        lockup_id
+    }
+
+    // Remove a transfer lockup - caller has to be contract owner
+    pub fn remove_transfer_lockup(&mut self, lockup_id: u64, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // remove the lockup and make sure it existed
+        assert!(
+            self.transfer_lockups.remove(&lockup_id).is_some(),
+            "No transfer lockup"
+        );
+
+        // construct the remove transfer lockup log
+        let remove_transfer_lockup_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::RemoveTransferLockup(vec![RemoveTransferLockupLog {
+                lockup_id,
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&remove_transfer_lockup_log.to_string());
+    }
+
+    //Query for the transfer lockups using pagination
+    pub fn transfer_lockups(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<TransferLockup> {
+        // where to start pagination - if we have a from_index, we'll use that - otherwise we start from 0
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.transfer_lockups.values()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    //Query for the series whose transfers are locked
+    pub fn locked_series(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<String> {
+        // where to start pagination - if we have a from_index, we'll use that - otherwise we start from 0
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.series_locked.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    //Query for the reason a token can't be transferred right now. Returns None if it can be transferred
+    pub fn nft_transfer_restriction(&self, token_id: TokenId) -> Option<String> {
+        assert!(self.tokens_by_id.contains_key(&token_id), "No token");
+        self.internal_transfer_restriction(&token_id)
+    }
+}
+
+impl Contract {
+    // get the reason why a token can't be transferred right now, if any
+    pub(crate) fn internal_transfer_restriction(&self, token_id: &TokenId) -> Option<String> {
+        let now = current_timestamp_ms();
+
+        // bonds of a soulbound contract can never be transferred
+        if self.soulbound {
+            return Some("Bonds of this contract are non-transferable".to_string());
+        }
+
+        // frozen tokens and tokens of frozen accounts can't be transferred
+        if self.frozen_tokens.contains(token_id) {
+            return Some("Token is frozen".to_string());
+        }
+        if let Some(token) = self.tokens_by_id.get(token_id) {
+            if self.frozen_accounts.contains(&token.owner_id) {
+                return Some(format!("Account {} is frozen", token.owner_id));
+            }
+        }
+
+        // tokens can't be transferred before they start being valid
+        if let Some(starts_at) = self.token_metadata_by_id.get(token_id).and_then(|metadata| metadata.starts_at) {
+            if now < starts_at {
+                return Some(format!("Token cannot be transferred before {}", starts_at));
+            }
+        }
+
+        // tokens of a locked series can't be transferred
+        let series_id = self.series_by_token_id.get(token_id);
+        if let Some(series_id) = series_id.as_ref() {
+            if self.series_locked.contains(series_id) {
+                return Some(format!("Transfers are locked for series {}", series_id));
+            }
+        }
+
+        // tokens bought in a campaign can't be transferred until the campaign succeeded
+        if let Some(series_id) = series_id.as_ref() {
+            let status = self.sales.get(series_id).and_then(|sale| sale.campaign_status(now));
+            if status.is_some() && status != Some(CampaignStatus::Succeeded) {
+                return Some(format!("Transfers are locked until the campaign for series {} succeeds", series_id));
+            }
+        }
+
+        // tokens can't be transferred during an active lockup that applies to them
+        self.transfer_lockups.values()
+            .find(|lockup| {
+                let applies = lockup.series_id.is_none() || lockup.series_id == series_id;
+                applies && lockup.starts_at <= now && now < lockup.ends_at
+            })
+            .map(|lockup| match lockup.reason {
+                Some(reason) => format!("Transfers are locked until {}: {}", lockup.ends_at, reason),
+                None => format!("Transfers are locked until {}", lockup.ends_at),
+            })
+    }
+}
@@ -1,5 +1,10 @@
 use crate::*;
 pub type TokenId = String;
+
+/// The highest yearly coupon rate a bond can have, in basis points
+pub const MAX_COUPON_RATE: u32 = 10_000;
+/// The allowed number of coupon payments per year (zero-coupon, annual, semi-annual, quarterly and monthly)
+pub const VALID_COUPON_FREQUENCIES: [u32; 5] = [0, 1, 2, 4, 12];
 //defines the payout type we'll be returning as a part of the royalty standards.
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
@@ -19,7 +24,7 @@ pub struct NFTContractMetadata {
     pub reference_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
 }
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct TokenMetadata {
     pub title: Option<String>, // ex. "Arch Nemesis: Mail Carrier" or "Parcel #5055"
@@ -36,6 +41,221 @@ pub struct TokenMetadata {
     pub reference_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
 }
 
+//defines the financial terms of a bond. These are validated when the bond is minted
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BondTerms {
+    pub face_value: U128, // principal amount in yoctoNEAR that is paid back at maturity
+    pub coupon_rate: u32, // yearly coupon rate in basis points, ex. 450 for 4.5%
+    pub coupon_frequency: u32, // number of coupon payments per year. Has to be 0 for zero-coupon bonds
+    pub issue_date: u64, // When the bond was issued, Unix epoch in milliseconds
+    pub maturity_date: u64, // When the bond matures, Unix epoch in milliseconds
+}
+
+impl BondTerms {
+    //panics if the terms do not describe a valid bond
+    pub(crate) fn assert_valid(&self) {
+        assert!(self.face_value.0 > 0, "Face value must be greater than 0");
+        assert!(
+            self.coupon_rate <= MAX_COUPON_RATE,
+            "Coupon rate cannot be more than {} basis points", MAX_COUPON_RATE
+        );
+        assert!(
+            VALID_COUPON_FREQUENCIES.contains(&self.coupon_frequency),
+            "Coupon frequency must be one of {:?}", VALID_COUPON_FREQUENCIES
+        );
+        assert_eq!(
+            self.coupon_rate == 0,
+            self.coupon_frequency == 0,
+            "Coupon frequency must be 0 if and only if the coupon rate is 0"
+        );
+        assert!(
+            self.maturity_date > self.issue_date,
+            "Maturity date must be after the issue date"
+        );
+    }
+}
+
+//defines how the fraction of a year between two dates is counted when calculating coupons
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DayCountConvention {
+    Actual365Fixed, // actual number of days divided by 365
+    Actual360, // actual number of days divided by 360
+    Thirty360, // every month counts as 30 days and every year as 360 days (US bond basis)
+}
+
+//defines a single payment in the cashflow schedule of a bond
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CouponPayment {
+    pub period_start: u64, // when the interest for the payment starts accruing, in milliseconds since the epoch
+    pub payment_date: u64, // when the payment is due, in milliseconds since the epoch
+    pub coupon: U128, // the coupon paid on the payment date in yoctoNEAR
+    pub principal: U128, // the principal paid on the payment date in yoctoNEAR, only set at maturity
+}
+
+//defines a series of bonds (a tranche) that share the same terms and metadata
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BondSeries {
+    pub series_id: String, // unique ID of the series, used as prefix for the token IDs
+    pub metadata: TokenMetadata, // template for the metadata of every token minted into the series
+    pub bond_terms: BondTerms, // the terms shared by every bond in the series
+    pub royalty: HashMap<AccountId, u32>, // the perpetual royalties given to every token minted into the series
+    pub max_supply: u64, // the maximum number of tokens that can be minted into the series
+    pub minted_count: u64, // the number of tokens that have been minted into the series, including burned ones
+    pub created_at: u64, // When the series was created, Unix epoch in milliseconds
+}
+
+//defines a period of time during which transfers are refused, ex. the record date blackout before a coupon
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferLockup {
+    pub lockup_id: u64, // sequential ID of the lockup, starting at 0
+    pub series_id: Option<String>, // the series the lockup applies to. Applies to every token if not set
+    pub starts_at: u64, // When the lockup starts, Unix epoch in milliseconds
+    pub ends_at: u64, // When the lockup ends, Unix epoch in milliseconds
+    pub reason: Option<String>, // free-form reason for the lockup, ex. "Record date for coupon 3"
+}
+
+//defines the user registry and municipality that receivers of transfers have to be registered in
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferRegistry {
+    pub registry_id: AccountId, // account ID of the user registry contract
+    pub municipality_id: String, // the municipality receivers have to be registered in
+}
+
+//defines the fee the platform receives on every sale that uses a payout
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PlatformFee {
+    pub receiver_id: AccountId, // the account the fee is paid to
+    pub fee: u32, // the fee in basis points
+}
+
+//defines a single token to mint in a batch
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchMintEntry {
+    pub token_id: TokenId, // the ID of the token to mint
+    pub metadata: TokenMetadata, // the metadata of the token
+    pub receiver_id: AccountId, // the account the token is minted to
+    pub bond_terms: Option<BondTerms>, // optional terms of the bond
+}
+
+//defines the terms of the primary sale of a series
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SaleConfig {
+    pub price: U128, // price per bond in yoctoNEAR
+    pub starts_at: u64, // when the sale opens, in milliseconds since the epoch
+    pub ends_at: u64, // when the sale closes, in milliseconds since the epoch
+    pub max_per_buyer: Option<u64>, // maximum number of bonds a single account can buy
+    pub max_total: Option<u64>, // maximum number of bonds sold in the sale
+    pub treasury_id: AccountId, // the account the proceeds are sent to
+    pub soft_cap: Option<U128>, // if set, the proceeds are held in escrow until the sale ends and refunded if less was raised
+}
+
+//defines a primary sale of a series
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Sale {
+    pub series_id: String, // the series being sold
+    pub config: SaleConfig, // the terms of the sale
+    pub sold_count: u64, // number of bonds sold so far
+    pub raised: U128, // total amount raised in yoctoNEAR
+    pub withdrawn: bool, // whether the escrowed proceeds were withdrawn to the treasury
+}
+
+impl Sale {
+    //get the status of the campaign if the sale has a soft cap
+    pub(crate) fn campaign_status(&self, now: u64) -> Option<CampaignStatus> {
+        self.config.soft_cap.map(|soft_cap| {
+            if now < self.config.ends_at {
+                CampaignStatus::Funding
+            } else if self.raised.0 >= soft_cap.0 {
+                CampaignStatus::Succeeded
+            } else {
+                CampaignStatus::Failed
+            }
+        })
+    }
+}
+
+//defines the status of a sale with a soft cap
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum CampaignStatus {
+    Funding, // the sale is running and the proceeds are held in escrow
+    Succeeded, // the soft cap was reached, the issuer can withdraw and the bonds can be transferred
+    Failed, // the soft cap wasn't reached, investors can claim refunds
+}
+
+//defines what an investor contributed to a campaign
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Contribution {
+    pub amount: U128, // the amount paid for the bonds in yoctoNEAR, excluding storage
+    pub token_ids: Vec<TokenId>, // the provisional bonds that were bought
+}
+
+//defines a snapshot of the ownership of every token
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Snapshot {
+    pub snapshot_id: u64, // index of the snapshot
+    pub taken_at: u64, // when the snapshot was taken, in milliseconds since the epoch
+    pub block_height: u64, // the block height the snapshot was taken at
+}
+
+//defines the owner of a token when a snapshot was taken. Copied on the first change after the snapshot
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct TokenCheckpoint {
+    pub snapshot_id: u64, // the latest snapshot when the token changed
+    pub owner_id: Option<AccountId>, // the owner at the snapshot, None if the token didn't exist yet
+}
+
+//defines the tokens of an account when a snapshot was taken. Copied on the first change after the snapshot
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OwnerCheckpoint {
+    pub snapshot_id: u64, // the latest snapshot when the tokens of the account changed
+    pub token_ids: Vec<TokenId>, // the tokens of the account at the snapshot
+}
+
+//defines a transfer in the ownership history of a token
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferRecord {
+    pub from_id: AccountId, // the previous owner
+    pub to_id: AccountId, // the new owner
+    pub timestamp: u64, // when the transfer happened, in milliseconds since the epoch
+    pub block_height: u64, // the block height of the transfer
+    pub memo: Option<String>, // the memo of the transfer, or the reason code of a forced transfer
+}
+
+//defines a coupon payment that was deposited by the owner and can be claimed per token
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CouponPeriod {
+    pub period_id: u64, // sequential ID of the coupon period, starting at 0
+    pub total_deposit: U128, // the amount in yoctoNEAR distributed among the eligible tokens
+    pub amount_per_token: U128, // the amount in yoctoNEAR each eligible token can claim
+    pub eligible_supply: u64, // number of tokens in existence when the coupon was deposited
+    pub mint_index_cutoff: u64, // only tokens with a mint index below this value can claim the coupon
+    pub deposited_at: u64, // When the coupon was deposited, Unix epoch in milliseconds
+    pub claimed_count: u64, // number of tokens that have claimed the coupon
+}
+
+//the coupon amount a token can still claim for a given period
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CouponEntitlement {
+    pub period_id: u64,
+    pub amount: U128,
+}
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Token {
     // owner of the token
@@ -58,16 +278,40 @@ pub struct JsonToken {
     // list of approved account ID's that have access to transfer the token. This maps an account ID to and approval ID
     pub approved_account_ids: HashMap<AccountId, u64>,
     pub royalty: HashMap<AccountId, u32>,
+    // the terms of the bond if they were provided at mint time
+    pub bond_terms: Option<BondTerms>,
+    // the series the token was minted into, if any
+    pub series_id: Option<String>,
+}
+
+//The Json contract metadata is what will be returned from nft_metadata. It adds the transfer mode to the metadata
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonContractMetadata {
+    #[serde(flatten)]
+    pub metadata: NFTContractMetadata,
+    // whether the bonds of the contract can never be transferred
+    pub soulbound: bool,
 }
 
 pub trait NonFungibleTokenMetadata {
     //view call for returning the contract metadata
-    fn nft_metadata(&self) -> NFTContractMetadata;
+    fn nft_metadata(&self) -> JsonContractMetadata;
 }
 
 #[near_bindgen]
 impl NonFungibleTokenMetadata for Contract {
-    fn nft_metadata(&self) -> NFTContractMetadata {
-        self.metadata.get().unwrap()
+    fn nft_metadata(&self) -> JsonContractMetadata {
+        JsonContractMetadata {
+            metadata: self.metadata.get().unwrap(),
+            soulbound: self.soulbound,
+        }
     }
-}
\ No newline at end of file
+}
+#[near_bindgen]
+impl Contract {
+    //view call for returning the bond terms of a token if it has any
+    pub fn nft_bond_terms(&self, token_id: TokenId) -> Option<BondTerms> {
+        self.bond_terms_by_id.get(&token_id)
+    }
+}
@@ -1,7 +1,8 @@
-use near_contract_standards::non_fungible_token::refund_deposit;
-
 use crate::*;
 
+/// The maximum number of tokens that can be minted in a single batch so the call stays within the GAS limit
+pub const MAX_BATCH_MINT_SIZE: usize = 50;
+
 #[near_bindgen]
 impl Contract {
     #[payable]
@@ -12,48 +13,20 @@ impl Contract {
         receiver_id: AccountId,
         // we add an optional parameter for perpetual royalties
         perpetual_royalties: Option<HashMap<AccountId, u32>>,
+        // we add an optional parameter for the terms of the bond
+        bond_terms: Option<BondTerms>,
     ) {
+        // make sure that only the owner or a minter can mint new bonds
+        self.assert_minter();
+
         // measure initial storage being used on the contract
         let initial_storage_usage = env::storage_usage();
 
-        // create a royalty map to store in the token
-        let mut royalty = HashMap::new();
+        // create a royalty map to store in the token. If no perpetual royalties were passed in, the default royalty is used
+        let royalty = self.internal_royalty(perpetual_royalties);
 
-        // if perpetual royalties were passed into the function
-        if let Some(perpetual_royalties) = perpetual_royalties {
-            // make sure that the length of the perpetual royalties is below 7 since we won't have enough GAS to pay out that many people
-            assert!(perpetual_royalties.len() < 7, "Cannot add more than 6 perpetual royalty amounts");
-
-            // iterate through the perpetual royalties and insert the account and amount in the royalty map
-            for (account, amount) in perpetual_royalties {
-                royalty.insert(account, amount);
-            }
-        }
-
-        // specify the token struct that contains the owner id
-        let token = Token {
-            // set the owner ID equal to the receiver ID passed into the function
-            owner_id: receiver_id,
-
-            // we set the approved account IDs to the default value (an empty map)
-            approved_account_ids: Default::default(),
-            // the next approval ID is set to 0,
-            next_approval_id: 0,
-            // the map of perpetual royalties for the token (The owner will get 100% - total perpetual royalties)
-            royalty,
-        };
-
-        // insert the token id and token struct and make sure that the token doesn't exist
-        assert!(
-            self.tokens_by_id.insert(&token_id, &token).is_none(),
-            "Token already exists"
-        );
-
-        // insert the token id and metadata
-        self.token_metadata_by_id.insert(&token_id, &metadata);
-
-        // call the internal method for adding the token to the owner
-        self.internal_add_token_to_owner(&token.owner_id, &token_id);
+        // mint the token to the receiver
+        self.internal_mint(&token_id, &metadata, &receiver_id, royalty, bond_terms, None);
 
         // contruct the mint log as per the events standard
         let nft_mint_log: EventLog = EventLog { 
@@ -61,7 +34,7 @@ impl Contract {
             version: NFT_METADATA_SPEC.to_string(), 
             event: EventLogVariant::NftMint(vec![NftMintLog {
                 // Owner of the token
-                owner_id: token.owner_id.to_string(),
+                owner_id: receiver_id.to_string(),
                 // vector of token IDs that were minted
                 token_ids: vec![token_id.to_string()],
                 // An optional memo to include
@@ -75,7 +48,71 @@ impl Contract {
         // calculate the required storage which was the used - initial
         let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
 
-        // refund any excess storage if the user attached too much. panic if they didnt attach enough to cover the required
-        refund_deposit(required_storage_in_bytes);
+        // take the storage from the prepaid balance of the user, or refund any excess storage if the user attached too much
+        self.internal_pay_storage(required_storage_in_bytes);
+    }
+
+    /*
+        Mint a batch of tokens in a single call - caller has to be the owner or a minter.
+        The perpetual royalties are shared by every token in the batch. If any token fails to mint, the whole batch is reverted.
+    */
+    #[payable]
+    pub fn nft_batch_mint(
+        &mut self,
+        entries: Vec<BatchMintEntry>,
+        // we add an optional parameter for perpetual royalties
+        perpetual_royalties: Option<HashMap<AccountId, u32>>,
+    ) {
+        // make sure that only the owner or a minter can mint new bonds
+        self.assert_minter();
+
+        // make sure the batch isn't empty and small enough to mint within the GAS limit
+        assert!(!entries.is_empty(), "Batch cannot be empty");
+        assert!(
+            entries.len() <= MAX_BATCH_MINT_SIZE,
+            "Cannot mint more than {} tokens in a batch", MAX_BATCH_MINT_SIZE
+        );
+
+        // measure initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        // create a royalty map to store in every token. If no perpetual royalties were passed in, the default royalty is used
+        let royalty = self.internal_royalty(perpetual_royalties);
+
+        // mint every token and group the minted token IDs by their owner
+        let mut nft_mint_logs: Vec<NftMintLog> = vec![];
+        for entry in entries {
+            self.internal_mint(&entry.token_id, &entry.metadata, &entry.receiver_id, royalty.clone(), entry.bond_terms, None);
+
+            let owner_id = entry.receiver_id.to_string();
+            if let Some(nft_mint_log) = nft_mint_logs.iter_mut().find(|log| log.owner_id == owner_id) {
+                nft_mint_log.token_ids.push(entry.token_id);
+            } else {
+                nft_mint_logs.push(NftMintLog {
+                    // Owner of the tokens
+                    owner_id,
+                    // vector of token IDs that were minted
+                    token_ids: vec![entry.token_id],
+                    // An optional memo to include
+                    memo: None,
+                });
+            }
+        }
+
+        // contruct the mint log as per the events standard
+        let nft_mint_log: EventLog = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftMint(nft_mint_logs),
+        };
+
+        // log the serialized json
+        env::log_str(&nft_mint_log.to_string());
+
+        // calculate the required storage which was the used - initial
+        let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
+
+        // take the storage from the prepaid balance of the user, or refund any excess storage if the user attached too much
+        self.internal_pay_storage(required_storage_in_bytes);
     }
-}
\ No newline at end of file
+}
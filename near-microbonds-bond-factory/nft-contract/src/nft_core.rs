@@ -1,9 +1,8 @@
 use crate::*;
-use near_contract_standards::non_fungible_token::refund_approved_account_ids;
 use near_sdk::{ext_contract, Gas, log, PromiseResult, assert_one_yocto};
 
-const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(10_000_000_000_000);
-const GAS_FOR_NFT_ON_TRANSFER: Gas = Gas(25_000_000_000_000);
+pub(crate) const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(10_000_000_000_000);
+pub(crate) const GAS_FOR_NFT_ON_TRANSFER: Gas = Gas(25_000_000_000_000);
 
 pub trait NonFungibleTokenCore {
     //transfers an NFT to a receiver ID
@@ -85,13 +84,28 @@ impl NonFungibleTokenCore for Contract {
         // get the sender to transfer the token from the sender to the receiver
         let sender_id = env::predecessor_account_id();
 
+        // if the contract is bound to a user registry, the receiver is checked before the token is transferred
+        if let Some(transfer_registry) = self.transfer_registry.clone() {
+            self.internal_check_receiver(
+                transfer_registry,
+                sender_id,
+                receiver_id,
+                token_id,
+                approval_id,
+                memo,
+                None
+            ).as_return();
+            return;
+        }
+
         // call the internal transfer method
         self.internal_transfer(
             &sender_id,
             &receiver_id,
             &token_id,
             approval_id,
-            memo
+            memo,
+            true
         );
     }
 
@@ -112,13 +126,69 @@ impl NonFungibleTokenCore for Contract {
         // get the sender ID
         let sender_id = env::predecessor_account_id();
 
-        // transfer the token and get the previous token object
+        // if the contract is bound to a user registry, the receiver is checked before the token is transferred
+        if let Some(transfer_registry) = self.transfer_registry.clone() {
+            return self.internal_check_receiver(
+                transfer_registry,
+                sender_id,
+                receiver_id,
+                token_id,
+                approval_id,
+                memo,
+                Some(msg)
+            ).into();
+        }
+
+        // transfer the token and call nft_on_transfer on the receiver
+        self.internal_transfer_call(sender_id, receiver_id, token_id, approval_id, memo, msg).into()
+    }
+
+    //get the information for a specific token ID
+    fn nft_token(&self, token_id: TokenId) -> Option<JsonToken> {
+        // if there is some token ID in the tokens_by_id collection
+        if let Some(token) = self.tokens_by_id.get(&token_id) {
+            // we'll get the metadata for that token
+            let metadata = self.token_metadata_by_id.get(&token_id).unwrap();
+            // and the bond terms if the token has any
+            let bond_terms = self.bond_terms_by_id.get(&token_id);
+            // and the series if the token was minted into one
+            let series_id = self.series_by_token_id.get(&token_id);
+
+            // we return the JsonToken (wrapped by Some since we return an option)
+            Some(JsonToken {
+                token_id,
+                owner_id: token.owner_id,
+                metadata,
+                approved_account_ids: token.approved_account_ids,
+                royalty: token.royalty,
+                bond_terms,
+                series_id,
+            })
+        } else { // if there wasn't a token ID in the tokens_by_id collection, we return None
+            None
+        }
+    }
+}
+
+impl Contract {
+    // transfer the token and call nft_on_transfer on the receiver, resolving the transfer afterwards
+    pub(crate) fn internal_transfer_call(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> Promise {
+        // transfer the token and get the previous token object. The approvals are refunded or restored when the transfer is resolved
         let previous_token = self.internal_transfer(
             &sender_id, 
             &receiver_id, 
             &token_id, 
             approval_id,
-            memo.clone()
+            memo.clone(),
+            false
         );
 
         //default the authorized_id to none
@@ -151,27 +221,7 @@ impl NonFungibleTokenCore for Contract {
                     previous_token.approved_account_ids,
                     memo, // we introduce a memo for logging in the events standard
                 )
-        ).into()
-    }
-
-    //get the information for a specific token ID
-    fn nft_token(&self, token_id: TokenId) -> Option<JsonToken> {
-        // if there is some token ID in the tokens_by_id collection
-        if let Some(token) = self.tokens_by_id.get(&token_id) {
-            // we'll get the metadata for that token
-            let metadata = self.token_metadata_by_id.get(&token_id).unwrap();
-            
-            // we return the JsonToken (wrapped by Some since we return an option)
-            Some(JsonToken {
-                token_id,
-                owner_id: token.owner_id,
-                metadata,
-                approved_account_ids: token.approved_account_ids,
-                royalty: token.royalty,
-            })
-        } else { // if there wasn't a token ID in the tokens_by_id collection, we return None
-            None
-        }
+        )
     }
 }
 
@@ -200,7 +250,7 @@ impl NonFungibleTokenResolver for Contract {
                 // if we don't need to return the token, we simply return true meaning everything went fine
                 if !return_token {
                     //we refund the owner for releasing the storage used up by the approved account IDs
-                    refund_approved_account_ids(owner_id, &approved_account_ids);
+                    self.internal_refund_approvals(&token_id, owner_id, &approved_account_ids);
                     return true;
                 }
             }
@@ -210,14 +260,14 @@ impl NonFungibleTokenResolver for Contract {
         let mut token = if let Some(token) = self.tokens_by_id.get(&token_id) {
             if token.owner_id != receiver_id {
                 //we refund the owner for releasing the storage used up by the approved account IDs
-                refund_approved_account_ids(owner_id, &approved_account_ids);
+                self.internal_refund_approvals(&token_id, owner_id, &approved_account_ids);
                 // the token is not owned by the receiver anymore. Can't return it
                 return true;
             }
             token
         } else { // if there isn't a token object, it was burned so we return true
             //we refund the owner for releasing the storage used up by the approved account IDs
-            refund_approved_account_ids(owner_id, &approved_account_ids);
+            self.internal_refund_approvals(&token_id, owner_id, &approved_account_ids);
             return true;
         };
 
@@ -233,13 +283,16 @@ impl NonFungibleTokenResolver for Contract {
         token.owner_id = owner_id.clone();
 
         //we refund the receiver any approved account IDs that they may have set on the token
-        refund_approved_account_ids(receiver_id.clone(), &token.approved_account_ids);
+        self.internal_refund_approvals(&token_id, receiver_id.clone(), &token.approved_account_ids);
         // reset the approved account IDs to what they were before the transfer
         token.approved_account_ids = approved_account_ids;
 
         // we insert the token back into the tokens_by_id collection
         self.tokens_by_id.insert(&token_id, &token);
 
+        // keep track of the returned token in the history of the token
+        self.internal_record_transfer(&token_id, &receiver_id, &owner_id, memo.clone());
+
         /*
             We need to log that the NFT was reverted back to the original owner.
             The old_owner_id will be the receiver and the new_owner_id will be the
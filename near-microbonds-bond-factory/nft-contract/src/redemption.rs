@@ -0,0 +1,125 @@
+use near_sdk::assert_one_yocto;
+
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    // Deposit funds that are used to pay out the principal of matured bonds - caller has to be contract owner
+    #[payable]
+    pub fn principal_deposit(&mut self, memo: Option<String>) -> U128 {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        let amount = env::attached_deposit();
+        assert!(amount > 0, "Requires attached deposit to fund the principal");
+
+        // add the deposit to the principal balance
+        self.principal_balance += amount;
+
+        // construct the principal deposit log
+        let principal_deposit_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::PrincipalDeposit(vec![PrincipalDepositLog {
+                amount: amount.to_string(),
+                principal_balance: self.principal_balance.to_string(),
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&principal_deposit_log.to_string());
+
+        U128(self.principal_balance)
+    }
+
+    /*
+        Redeem a matured bond - caller has to be the token owner or an approved account, or the contract owner for soulbound bonds.
+        The token owner is paid the face value and any unclaimed coupons, and is refunded for the approvals they added.
+        The storage of the token itself was paid by whoever minted or bought it, so it stays in the contract.
+    */
+    #[payable]
+    pub fn nft_redeem(
+        &mut self,
+        token_id: TokenId,
+        // we introduce an approval ID so that people with that approval ID can redeem the token
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) -> U128 {
+        // assert that the user attached exactly 1 yoctoNEAR for security reasons
+        assert_one_yocto();
+
+        // make sure the sender is allowed to redeem the token
+        let sender_id = env::predecessor_account_id();
+        let token = self.tokens_by_id.get(&token_id).expect("No token");
+        // bonds of a soulbound contract can't be approved, so the contract owner can redeem them on behalf of the holder
+        if !(self.soulbound && sender_id == self.owner_id) {
+            self.internal_assert_owner_or_approved(&token_id, &token, &sender_id, approval_id);
+        }
+
+        // frozen tokens and tokens of frozen accounts can't be cashed out either
+        assert!(!self.frozen_tokens.contains(&token_id), "Token is frozen");
+        assert!(
+            !self.frozen_accounts.contains(&token.owner_id),
+            "Account {} is frozen", token.owner_id
+        );
+
+        // make sure the bond has matured
+        let bond_terms = self.bond_terms_by_id.get(&token_id).expect("Token has no bond terms");
+        assert!(
+            current_timestamp_ms() >= bond_terms.maturity_date,
+            "Bond has not matured yet"
+        );
+
+        // make sure there are enough funds to pay out the principal
+        let principal = bond_terms.face_value.0;
+        assert!(
+            self.principal_balance >= principal,
+            "Not enough principal funded to redeem the bond"
+        );
+        self.principal_balance -= principal;
+
+        // pay out any coupons the token didn't claim yet since it can't claim them after being burned
+        let coupon = self.internal_settle_coupons(&token_id);
+
+        // if the sender isn't the owner of the token, we set the authorized ID equal to the sender
+        let authorized_id = if sender_id != token.owner_id {
+            Some(sender_id.to_string())
+        } else {
+            None
+        };
+
+        // the owner paid for the approvals of the token so they get that storage back
+        self.internal_refund_approvals(&token_id, token.owner_id.clone(), &token.approved_account_ids);
+
+        // burn the token
+        let token = self.internal_burn(&token_id, authorized_id, memo);
+
+        // construct the redeem log
+        let bond_redeem_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::BondRedeem(vec![BondRedeemLog {
+                owner_id: token.owner_id.to_string(),
+                token_id,
+                principal: principal.to_string(),
+                coupon: coupon.to_string(),
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&bond_redeem_log.to_string());
+
+        // pay out the principal and coupons to the token owner
+        let amount = principal + coupon;
+        Promise::new(token.owner_id).transfer(amount);
+
+        U128(amount)
+    }
+
+    //Query for the amount of funds available to pay out principal
+    pub fn principal_balance(&self) -> U128 {
+        U128(self.principal_balance)
+    }
+}
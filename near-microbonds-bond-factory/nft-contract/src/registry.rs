@@ -0,0 +1,122 @@
+use near_sdk::{ext_contract, Gas, PromiseResult};
+
+use crate::*;
+
+const GAS_FOR_RECEIVER_CHECK: Gas = Gas(5_000_000_000_000);
+const GAS_FOR_RESOLVE_RECEIVER_CHECK: Gas = Gas(15_000_000_000_000);
+
+#[ext_contract(ext_user_registry)]
+trait UserRegistry {
+    //view call on the user registry that checks if an account is registered in a municipality
+    fn is_account_in_municipality(&self, municipality_id: String, account_id: AccountId) -> bool;
+}
+
+#[near_bindgen]
+impl Contract {
+    /*
+        Bind the contract to a user registry so that receivers of transfers have to be registered in the municipality.
+        Passing in None unbinds the contract - caller has to be contract owner.
+    */
+    pub fn set_transfer_registry(&mut self, transfer_registry: Option<TransferRegistry>, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        self.transfer_registry = transfer_registry.clone();
+
+        // construct the set transfer registry log
+        let set_transfer_registry_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SetTransferRegistry(vec![SetTransferRegistryLog {
+                registry_id: transfer_registry.as_ref().map(|registry| registry.registry_id.to_string()),
+                municipality_id: transfer_registry.map(|registry| registry.municipality_id),
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&set_transfer_registry_log.to_string());
+    }
+
+    //Query for the user registry the contract is bound to, if any
+    pub fn transfer_registry(&self) -> Option<TransferRegistry> {
+        self.transfer_registry.clone()
+    }
+
+    /*
+        resolves the cross contract call to the user registry that checks the receiver of a transfer.
+        If the receiver is registered, the transfer happens. Otherwise nothing was changed and we panic.
+        If a msg is passed in, the transfer continues like nft_transfer_call.
+    */
+    #[private]
+    pub fn nft_resolve_receiver_check(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: Option<String>,
+    ) -> PromiseOrValue<bool> {
+        // get whether the receiver is registered in the municipality
+        let is_eligible = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                near_sdk::serde_json::from_slice::<bool>(&value).unwrap_or(false)
+            },
+            _ => env::panic_str("Failed to check the receiver against the user registry"),
+        };
+
+        if !is_eligible {
+            env::panic_str("Receiver is not registered in the municipality");
+        }
+
+        // the receiver is registered so we perform the transfer that was requested
+        if let Some(msg) = msg {
+            self.internal_transfer_call(sender_id, receiver_id, token_id, approval_id, memo, msg).into()
+        } else {
+            self.internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, memo, true);
+            PromiseOrValue::Value(true)
+        }
+    }
+}
+
+impl Contract {
+    // check the receiver against the user registry and only transfer the token if they are registered
+    pub(crate) fn internal_check_receiver(
+        &self,
+        transfer_registry: TransferRegistry,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: Option<String>,
+    ) -> Promise {
+        // fail early if the transfer wouldn't be possible, it is checked again once the receiver is checked
+        self.internal_assert_can_transfer(&sender_id, &receiver_id, &token_id, approval_id);
+
+        // the callback needs enough GAS to call nft_on_transfer if a msg was passed in
+        let gas_for_resolve = if msg.is_some() {
+            GAS_FOR_RESOLVE_RECEIVER_CHECK + GAS_FOR_NFT_ON_TRANSFER + GAS_FOR_RESOLVE_TRANSFER
+        } else {
+            GAS_FOR_RESOLVE_RECEIVER_CHECK
+        };
+
+        ext_user_registry::ext(transfer_registry.registry_id)
+            .with_static_gas(GAS_FOR_RECEIVER_CHECK)
+            .is_account_in_municipality(transfer_registry.municipality_id, receiver_id.clone())
+        .then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(gas_for_resolve)
+                .nft_resolve_receiver_check(
+                    sender_id,
+                    receiver_id,
+                    token_id,
+                    approval_id,
+                    memo,
+                    msg
+                )
+        )
+    }
+}
@@ -0,0 +1,144 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    // Add an account that is allowed to mint tokens - caller has to be contract owner
+    pub fn add_minter(&mut self, account_id: AccountId, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // insert the account into the set of minters and make sure it isn't already a minter
+        assert!(
+            self.minters.insert(&account_id),
+            "Account is already a minter"
+        );
+
+        // construct the add minter log
+        let add_minter_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::AddMinter(vec![AddMinterLog {
+                // the account that is now allowed to mint
+                account_id: account_id.to_string(),
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&add_minter_log.to_string());
+    }
+
+    // Remove an account from the minters - caller has to be contract owner
+    pub fn remove_minter(&mut self, account_id: AccountId, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // remove the account from the set of minters and make sure it was a minter
+        assert!(
+            self.minters.remove(&account_id),
+            "Account is not a minter"
+        );
+
+        // construct the remove minter log
+        let remove_minter_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::RemoveMinter(vec![RemoveMinterLog {
+                // the account that is no longer allowed to mint
+                account_id: account_id.to_string(),
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&remove_minter_log.to_string());
+    }
+
+    // Check if an account is allowed to mint tokens
+    pub fn is_minter(&self, account_id: AccountId) -> bool {
+        account_id == self.owner_id || self.minters.contains(&account_id)
+    }
+
+    // Query for the accounts that were added as minters using pagination
+    pub fn get_minters(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<AccountId> {
+        // where to start pagination - if we have a from_index, we'll use that - otherwise we start from 0
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.minters.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    // Add an account that is allowed to force transfers and freeze tokens and accounts - caller has to be contract owner
+    pub fn add_compliance_officer(&mut self, account_id: AccountId, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // insert the account into the set of compliance officers and make sure it isn't already one
+        assert!(
+            self.compliance_officers.insert(&account_id),
+            "Account is already a compliance officer"
+        );
+
+        // construct the add compliance officer log
+        let add_compliance_officer_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::AddComplianceOfficer(vec![AddComplianceOfficerLog {
+                // the account that is now a compliance officer
+                account_id: account_id.to_string(),
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&add_compliance_officer_log.to_string());
+    }
+
+    // Remove an account from the compliance officers - caller has to be contract owner
+    pub fn remove_compliance_officer(&mut self, account_id: AccountId, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // remove the account from the set of compliance officers and make sure it was one
+        assert!(
+            self.compliance_officers.remove(&account_id),
+            "Account is not a compliance officer"
+        );
+
+        // construct the remove compliance officer log
+        let remove_compliance_officer_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::RemoveComplianceOfficer(vec![RemoveComplianceOfficerLog {
+                // the account that is no longer a compliance officer
+                account_id: account_id.to_string(),
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&remove_compliance_officer_log.to_string());
+    }
+
+    // Check if an account is allowed to force transfers and freeze tokens and accounts
+    pub fn is_compliance_officer(&self, account_id: AccountId) -> bool {
+        account_id == self.owner_id || self.compliance_officers.contains(&account_id)
+    }
+
+    // Query for the accounts that were added as compliance officers using pagination
+    pub fn get_compliance_officers(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<AccountId> {
+        // where to start pagination - if we have a from_index, we'll use that - otherwise we start from 0
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.compliance_officers.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+}
@@ -1,10 +1,108 @@
 use std::hash::Hash;
 
-use near_contract_standards::non_fungible_token::refund_approved_account_ids;
+use near_contract_standards::non_fungible_token::refund_deposit;
 use near_sdk::assert_one_yocto;
 
 use crate::*;
 
+/// The maximum number of perpetual royalty recipients of a token since we won't have enough GAS to pay out more
+pub const MAX_ROYALTY_RECIPIENTS: usize = 6;
+/// Royalties are in basis points, so the royalties of a token can add up to at most 100%
+pub const MAX_TOTAL_ROYALTY: u32 = 10_000;
+
+//panics if the royalty schedule can't be paid out
+pub(crate) fn assert_valid_royalty(royalty: &HashMap<AccountId, u32>) {
+    assert!(
+        royalty.len() <= MAX_ROYALTY_RECIPIENTS,
+        "Cannot add more than {} perpetual royalty amounts", MAX_ROYALTY_RECIPIENTS
+    );
+    let total: u64 = royalty.values().map(|amount| *amount as u64).sum();
+    assert!(
+        total <= MAX_TOTAL_ROYALTY as u64,
+        "Royalties cannot add up to more than {} basis points", MAX_TOTAL_ROYALTY
+    );
+}
+
+/*
+    get the share of every receiver of a payout in basis points.
+    The platform fee receiver and the perpetual royalties are paid first, the owner gets what is left of the 100%.
+    If the royalties don't fit next to the platform fee, they are scaled down so they share what is left after the fee.
+*/
+pub(crate) fn royalty_split(
+    royalty: &HashMap<AccountId, u32>,
+    owner_id: &AccountId,
+    platform_fee: Option<&PlatformFee>,
+) -> HashMap<AccountId, u32> {
+    let mut split: HashMap<AccountId, u32> = HashMap::new();
+    let mut total: u64 = 0;
+
+    //the platform fee receiver can also be a royalty receiver or the owner so the shares are added up
+    if let Some(platform_fee) = platform_fee {
+        *split.entry(platform_fee.receiver_id.clone()).or_insert(0) += platform_fee.fee;
+        total += platform_fee.fee as u64;
+    }
+
+    //the royalties can share what the platform fee leaves. The fee can be raised after tokens were minted, so they can be more than that
+    let available = MAX_TOTAL_ROYALTY as u64 - total;
+    let royalty_total: u64 = royalty.iter()
+        .filter(|(account_id, _)| *account_id != owner_id)
+        .map(|(_, amount)| *amount as u64)
+        .sum();
+
+    //only insert the royalties that aren't for the token owner (we add their share at the end)
+    for (account_id, amount) in royalty.iter() {
+        if account_id != owner_id {
+            let amount = if royalty_total > available {
+                *amount as u64 * available / royalty_total
+            } else {
+                *amount as u64
+            };
+            *split.entry(account_id.clone()).or_insert(0) += amount as u32;
+            total += amount;
+        }
+    }
+
+    *split.entry(owner_id.clone()).or_insert(0) += MAX_TOTAL_ROYALTY - total as u32;
+    split
+}
+
+/*
+    calculate the payout of a sale. Every receiver, including the owner, counts towards the max length of the payout.
+    The amounts are rounded down and the rounding dust is given to the owner so the payout always adds up to the balance.
+*/
+pub(crate) fn compute_payout(
+    royalty: &HashMap<AccountId, u32>,
+    owner_id: &AccountId,
+    platform_fee: Option<&PlatformFee>,
+    balance: Balance,
+    max_len_payout: u32,
+) -> Payout {
+    let split = royalty_split(royalty, owner_id, platform_fee);
+
+    // make sure we're not paying out to too many people (GAS limits this)
+    assert!(split.len() as u32 <= max_len_payout, "Market cannot payout to that many receivers");
+
+    // keep track of the payout object to send back
+    let mut payout_object = Payout {
+        payout: HashMap::new()
+    };
+
+    // pay out everyone but the owner and keep track of the total that was paid
+    let mut total_paid: Balance = 0;
+    for (account_id, amount) in split {
+        if &account_id != owner_id {
+            let payout = royalty_to_payout(amount, balance);
+            total_paid += payout.0;
+            payout_object.payout.insert(account_id, payout);
+        }
+    }
+
+    // the owner gets the rest of the balance, including the rounding dust
+    payout_object.payout.insert(owner_id.clone(), U128(balance - total_paid));
+
+    payout_object
+}
+
 pub trait NonFungibleTokenCore {
     //calculates the payout for a token given the passed in balance. This is a view method
   	fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: u32) -> Payout;
@@ -29,38 +127,14 @@ impl NonFungibleTokenCore for Contract {
 		// get the token object
         let token = self.tokens_by_id.get(&token_id).expect("No token");
 
-        // get the owner of the token
-        let owner_id = token.owner_id;
-        // keep track of the total perpetual royalties
-        let mut total_perpetual = 0;
-        // get the u128 version of the passed in balance (which was U128 before)
-        let balance_u128 = u128::from(balance);
-        // keep track of the payout object to send back
-        let mut payout_object = Payout {
-            payout: HashMap::new()
-        };
-        // get the royalty object from token
-        let royalty = token.royalty;
-
-        // make sure we're not paying out to too many people (GAS limits this)
-        assert!(royalty.len() as u32 <= max_len_payout, "Market cannot payout to that many receivers");
-
-        // go through each key and value in the royalty object
-        for (k, v) in royalty.iter() {
-            // get the key
-            let key = k.clone();
-            //only insert into the payout if the key isn't the token owner (we add their payout at the end)
-            if key != owner_id {
-                payout_object.payout.insert(key, royalty_to_payout(*v, balance_u128));
-                total_perpetual += *v;
-            }
-        }
-
-        // payout to previous owner who gets 100% - total perpetual royalties
-        payout_object.payout.insert(owner_id, royalty_to_payout(10000 - total_perpetual, balance_u128));
-
-        // return the payout object
-        payout_object
+        // calculate the payout for the owner, the platform fee and the perpetual royalties
+        compute_payout(
+            &token.royalty,
+            &token.owner_id,
+            self.platform_fee.as_ref(),
+            balance.0,
+            max_len_payout
+        )
 	}
 
     //transfers the token to the receiver ID and returns the payout object that should be payed given the passed in balance. 
@@ -76,56 +150,155 @@ impl NonFungibleTokenCore for Contract {
     ) -> Payout {
         // assert that the user attached 1 yocto NEAR for security reasons
         assert_one_yocto();
+        // the receiver can't be checked against the user registry since the payout has to be returned right away
+        assert!(
+            self.transfer_registry.is_none(),
+            "Transfers with payout are disabled while bound to a user registry"
+        );
         // get the sender ID
         let sender_id = env::predecessor_account_id();
         // transfer the token to the passed in receiver and get the previous token object back
+        // the previous token owner is refunded for the storage used up by the previous approved account IDs
         let previous_token = self.internal_transfer(
             &sender_id, 
             &receiver_id, 
             &token_id, 
             Some(approval_id), 
-            memo
+            memo,
+            true
         );
 
-        // refund the previous token owner for the storage used up by the previous approved account IDs
-        refund_approved_account_ids(
-            previous_token.owner_id.clone(), 
-            &previous_token.approved_account_ids
-        );
+        // calculate the payout for the previous owner, the platform fee and the perpetual royalties
+        compute_payout(
+            &previous_token.royalty,
+            &previous_token.owner_id,
+            self.platform_fee.as_ref(),
+            balance.0,
+            max_len_payout
+        )
+    }
+}
 
-        // get the owner of the token
-        let owner_id = previous_token.owner_id;
-        //keep track of the total perpetual royalties
-        let mut total_perpetual = 0;
-        // get the u128 version of the passed in balance (which was U128 before)
-        let balance_u128 = u128::from(balance);
-        // keep track of the payout object to send back
-        let mut payout_object = Payout {
-            payout: HashMap::new()
+#[near_bindgen]
+impl Contract {
+    // Set the royalty that is used for future mints when no perpetual royalties are passed in - caller has to be contract owner
+    pub fn set_default_royalty(&mut self, royalty: HashMap<AccountId, u32>, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // make sure the royalty can be paid out
+        assert_valid_royalty(&royalty);
+        self.default_royalty = royalty.clone();
+
+        // construct the set default royalty log
+        let set_default_royalty_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SetDefaultRoyalty(vec![SetDefaultRoyaltyLog {
+                royalty,
+                // an optional memo to include
+                memo,
+            }]),
         };
-        // get the royalty object from token
-        let royalty = previous_token.royalty;
-
-        // make sure we're not paying out to too many people (GAS limits this)
-        assert!(royalty.len() as u32 <= max_len_payout, "Market cannot payout to that many receivers");
-
-        // go through each key and value in the royalty object
-        for (k, v) in royalty.iter() {
-            //get the key
-            let key = k.clone();
-            //only insert into the payout if the key isn't the token owner (we add their payout at the end)
-            if key != owner_id {
-                //
-                payout_object.payout.insert(key, royalty_to_payout(*v, balance_u128));
-                total_perpetual += *v;
-            }
+
+        // log the serialized json
+        env::log_str(&set_default_royalty_log.to_string());
+    }
+
+    //Query for the royalty that is used for future mints when no perpetual royalties are passed in
+    pub fn default_royalty(&self) -> HashMap<AccountId, u32> {
+        self.default_royalty.clone()
+    }
+
+    // Replace the perpetual royalties of an existing token - caller has to be contract owner
+    #[payable]
+    pub fn nft_update_royalty(&mut self, token_id: TokenId, royalty: HashMap<AccountId, u32>, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // measure initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        // make sure the royalty can be paid out
+        assert_valid_royalty(&royalty);
+
+        let mut token = self.tokens_by_id.get(&token_id).expect("No token");
+        token.royalty = royalty.clone();
+        self.tokens_by_id.insert(&token_id, &token);
+
+        // construct the update royalty log
+        let update_royalty_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::UpdateRoyalty(vec![UpdateRoyaltyLog {
+                token_id,
+                royalty,
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&update_royalty_log.to_string());
+
+        // calculate the required storage which was the used - initial
+        let required_storage_in_bytes = env::storage_usage().saturating_sub(initial_storage_usage);
+
+        // refund any excess storage if the user attached too much. panic if they didnt attach enough to cover the required
+        refund_deposit(required_storage_in_bytes);
+    }
+
+    //Query for the share of a sale every receiver of a token's payout gets, in basis points. This includes the token owner and the platform fee
+    pub fn nft_royalty_split(&self, token_id: TokenId) -> HashMap<AccountId, u32> {
+        let token = self.tokens_by_id.get(&token_id).expect("No token");
+        royalty_split(&token.royalty, &token.owner_id, self.platform_fee.as_ref())
+    }
+
+    // Set the fee the platform receives on every sale that uses a payout - caller has to be contract owner
+    pub fn set_platform_fee(&mut self, platform_fee: Option<PlatformFee>, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        if let Some(platform_fee) = platform_fee.as_ref() {
+            assert!(
+                platform_fee.fee <= MAX_TOTAL_ROYALTY,
+                "Platform fee cannot be more than {} basis points", MAX_TOTAL_ROYALTY
+            );
         }
+        self.platform_fee = platform_fee.clone();
 
-        // payout to previous owner who gets 100% - total perpetual royalties
-        payout_object.payout.insert(owner_id, royalty_to_payout(10000 - total_perpetual, balance_u128));
+        // construct the set platform fee log
+        let set_platform_fee_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SetPlatformFee(vec![SetPlatformFeeLog {
+                receiver_id: platform_fee.as_ref().map(|platform_fee| platform_fee.receiver_id.to_string()),
+                fee: platform_fee.map(|platform_fee| platform_fee.fee),
+                // an optional memo to include
+                memo,
+            }]),
+        };
 
-        // return the payout object
-        payout_object
+        // log the serialized json
+        env::log_str(&set_platform_fee_log.to_string());
+    }
 
+    //Query for the fee the platform receives on every sale that uses a payout, if any
+    pub fn platform_fee(&self) -> Option<PlatformFee> {
+        self.platform_fee.clone()
     }
-}
\ No newline at end of file
+}
+
+impl Contract {
+    // get the royalty to store in a newly minted token. If no perpetual royalties were passed in, the default royalty is used
+    pub(crate) fn internal_royalty(&self, perpetual_royalties: Option<HashMap<AccountId, u32>>) -> HashMap<AccountId, u32> {
+        match perpetual_royalties {
+            Some(perpetual_royalties) => {
+                // make sure the royalty can be paid out
+                assert_valid_royalty(&perpetual_royalties);
+                perpetual_royalties
+            },
+            None => self.default_royalty.clone(),
+        }
+    }
+}
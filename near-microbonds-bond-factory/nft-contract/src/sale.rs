@@ -0,0 +1,236 @@
+use near_contract_standards::non_fungible_token::refund_deposit;
+
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /*
+        Open or update the primary sale of a series - caller has to be contract owner.
+        Investors can buy the bonds of the series with `buy` while the sale is running.
+    */
+    #[payable]
+    pub fn configure_sale(&mut self, series_id: String, config: SaleConfig, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // measure initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        // make sure the series exists and the sale is valid
+        assert!(self.series_by_id.get(&series_id).is_some(), "No series");
+        assert!(config.ends_at > config.starts_at, "Sale must end after it starts");
+        assert!(config.max_per_buyer != Some(0), "Per-buyer cap must be greater than 0");
+        assert!(config.max_total != Some(0), "Total cap must be greater than 0");
+        assert!(config.soft_cap != Some(U128(0)), "Soft cap must be greater than 0");
+
+        // keep track of what was sold if the sale is being updated
+        let sale = if let Some(mut sale) = self.sales.get(&series_id) {
+            // the escrow depends on the soft cap so it can't change once bonds are sold
+            assert!(
+                sale.sold_count == 0 || sale.config.soft_cap == config.soft_cap,
+                "Cannot change the soft cap after bonds were sold"
+            );
+            // whether a campaign succeeds depends on its deadline and price, so they can't change either
+            assert!(
+                sale.sold_count == 0
+                    || sale.config.soft_cap.is_none()
+                    || (sale.config.ends_at == config.ends_at && sale.config.price == config.price),
+                "Cannot change the deadline or price of a campaign after bonds were sold"
+            );
+            sale.config = config;
+            sale
+        } else {
+            Sale {
+                series_id: series_id.clone(),
+                config,
+                sold_count: 0,
+                raised: U128(0),
+                withdrawn: false,
+            }
+        };
+        self.sales.insert(&series_id, &sale);
+
+        // construct the configure sale log
+        let configure_sale_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::ConfigureSale(vec![ConfigureSaleLog {
+                series_id,
+                price: sale.config.price.0.to_string(),
+                starts_at: sale.config.starts_at,
+                ends_at: sale.config.ends_at,
+                max_per_buyer: sale.config.max_per_buyer,
+                max_total: sale.config.max_total,
+                treasury_id: sale.config.treasury_id.to_string(),
+                soft_cap: sale.config.soft_cap.map(|soft_cap| soft_cap.0.to_string()),
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&configure_sale_log.to_string());
+
+        // calculate the required storage which was the used - initial
+        let required_storage_in_bytes = env::storage_usage().saturating_sub(initial_storage_usage);
+
+        // refund any excess storage if the user attached too much. panic if they didnt attach enough to cover the required
+        refund_deposit(required_storage_in_bytes);
+    }
+
+    // End the primary sale of a series - caller has to be contract owner
+    pub fn end_sale(&mut self, series_id: String, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // campaigns end at their deadline since the escrow is settled based on it
+        let sale = self.sales.get(&series_id).expect("No sale");
+        assert!(sale.config.soft_cap.is_none(), "Campaigns cannot be ended before their deadline");
+        self.sales.remove(&series_id);
+
+        // construct the end sale log
+        let end_sale_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::EndSale(vec![EndSaleLog {
+                series_id,
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&end_sale_log.to_string());
+    }
+
+    /*
+        Buy bonds of a series that is on sale. The bonds are minted to the caller and the proceeds are sent to the treasury.
+        If the sale has a soft cap, the proceeds are held in escrow until the campaign is settled instead.
+        The caller has to attach the price of the bonds plus the storage used to mint them. Any overpayment is refunded.
+    */
+    #[payable]
+    pub fn buy(&mut self, series_id: String, quantity: u64) -> Vec<TokenId> {
+        let buyer_id = env::predecessor_account_id();
+        let mut sale = self.sales.get(&series_id).expect("No sale");
+
+        // make sure the sale is running
+        let now = current_timestamp_ms();
+        assert!(now >= sale.config.starts_at, "Sale has not started yet");
+        assert!(now < sale.config.ends_at, "Sale has ended");
+
+        // make sure the purchase stays within the caps of the sale
+        assert!(quantity > 0, "Quantity must be greater than 0");
+        assert!(
+            quantity as usize <= MAX_BATCH_MINT_SIZE,
+            "Cannot buy more than {} bonds at once", MAX_BATCH_MINT_SIZE
+        );
+        if let Some(max_total) = sale.config.max_total {
+            assert!(sale.sold_count + quantity <= max_total, "Not enough bonds left in the sale");
+        }
+        let purchased = self.purchases_per_buyer.get(&(series_id.clone(), buyer_id.clone())).unwrap_or(0);
+        if let Some(max_per_buyer) = sale.config.max_per_buyer {
+            assert!(purchased + quantity <= max_per_buyer, "Purchase exceeds the per-buyer cap");
+        }
+
+        // measure initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        // mint the bonds to the buyer
+        let token_ids: Vec<TokenId> = (0..quantity)
+            .map(|_| self.internal_mint_series(&series_id, &buyer_id))
+            .collect();
+
+        // keep track of the bonds sold
+        let price = sale.config.price.0 * quantity as u128;
+        sale.sold_count += quantity;
+        sale.raised = U128(sale.raised.0 + price);
+        self.sales.insert(&series_id, &sale);
+        self.purchases_per_buyer.insert(&(series_id.clone(), buyer_id.clone()), &(purchased + quantity));
+
+        // if the proceeds are held in escrow, keep track of the contribution so it can be refunded
+        let is_campaign = sale.config.soft_cap.is_some();
+        if is_campaign {
+            let key = (series_id.clone(), buyer_id.clone());
+            let mut contribution = self.contributions.get(&key).unwrap_or(Contribution {
+                amount: U128(0),
+                token_ids: vec![],
+            });
+            contribution.amount = U128(contribution.amount.0 + price);
+            contribution.token_ids.extend(token_ids.iter().cloned());
+            self.contributions.insert(&key, &contribution);
+        }
+
+        // the buyer pays for the bonds and the storage used to mint them
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        let required = price + env::STORAGE_PRICE_PER_BYTE * storage_used as u128;
+        let attached_deposit = env::attached_deposit();
+        assert!(
+            attached_deposit >= required,
+            "Must attach {} yoctoNEAR to cover the price and storage", required
+        );
+
+        // contruct the mint log as per the events standard
+        let nft_mint_log: EventLog = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftMint(vec![NftMintLog {
+                // Owner of the tokens
+                owner_id: buyer_id.to_string(),
+                // vector of token IDs that were minted
+                token_ids: token_ids.clone(),
+                // An optional memo to include
+                memo: None,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&nft_mint_log.to_string());
+
+        // construct the bond purchase log
+        let bond_purchase_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::BondPurchase(vec![BondPurchaseLog {
+                buyer_id: buyer_id.to_string(),
+                series_id,
+                token_ids: token_ids.clone(),
+                price: price.to_string(),
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&bond_purchase_log.to_string());
+
+        // forward the proceeds to the treasury unless they are held in escrow and refund any overpayment to the buyer
+        if price > 0 && !is_campaign {
+            Promise::new(sale.config.treasury_id).transfer(price);
+        }
+        let refund = attached_deposit - required;
+        if refund > 0 {
+            Promise::new(buyer_id).transfer(refund);
+        }
+
+        token_ids
+    }
+
+    //Query for the sales on the contract using pagination
+    pub fn sales(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Sale> {
+        // where to start pagination - if we have a from_index, we'll use that - otherwise we start from 0
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.sales.values()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    //Query for the sale of a specific series
+    pub fn sale_info(&self, series_id: String) -> Option<Sale> {
+        self.sales.get(&series_id)
+    }
+
+    //Query for the number of bonds of a series an account has bought in the sale
+    pub fn sale_purchased_by(&self, series_id: String, account_id: AccountId) -> u64 {
+        self.purchases_per_buyer.get(&(series_id, account_id)).unwrap_or(0)
+    }
+}
@@ -0,0 +1,209 @@
+use near_contract_standards::non_fungible_token::refund_deposit;
+
+use crate::*;
+use crate::nft_core::NonFungibleTokenCore;
+
+/// The delimiter between the series ID and the sequence number in the token IDs of a series
+pub const SERIES_DELIMITER: &str = ":";
+
+#[near_bindgen]
+impl Contract {
+    // Create a new bond series that tokens can be minted into - caller has to be contract owner
+    #[payable]
+    pub fn create_series(
+        &mut self,
+        series_id: String,
+        metadata: TokenMetadata,
+        bond_terms: BondTerms,
+        max_supply: u64,
+        // we add an optional parameter for perpetual royalties
+        perpetual_royalties: Option<HashMap<AccountId, u32>>,
+        memo: Option<String>,
+    ) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // measure initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        // make sure the series ID can be used as a prefix for the token IDs
+        assert!(!series_id.is_empty(), "Series ID cannot be empty");
+        assert!(
+            !series_id.contains(SERIES_DELIMITER),
+            "Series ID cannot contain '{}'", SERIES_DELIMITER
+        );
+        assert!(max_supply > 0, "Max supply must be greater than 0");
+
+        // make sure the terms shared by the bonds in the series are valid
+        bond_terms.assert_valid();
+
+        // create a royalty map to store in the series. If no perpetual royalties were passed in, the default royalty is used
+        let royalty = self.internal_royalty(perpetual_royalties);
+
+        let series = BondSeries {
+            series_id: series_id.clone(),
+            metadata,
+            bond_terms,
+            royalty,
+            max_supply,
+            minted_count: 0,
+            created_at: current_timestamp_ms(),
+        };
+
+        // insert the series and make sure it doesn't already exist
+        assert!(
+            self.series_by_id.insert(&series_id, &series).is_none(),
+            "Series already exists"
+        );
+
+        // construct the create series log
+        let create_series_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::CreateSeries(vec![CreateSeriesLog {
+                series_id,
+                max_supply,
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&create_series_log.to_string());
+
+        // calculate the required storage which was the used - initial
+        let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
+
+        // refund any excess storage if the user attached too much. panic if they didnt attach enough to cover the required
+        refund_deposit(required_storage_in_bytes);
+    }
+
+    // Mint the next token of a series to the receiver - caller has to be the owner or a minter
+    #[payable]
+    pub fn nft_mint_series(&mut self, series_id: String, receiver_id: AccountId) -> TokenId {
+        // make sure that only the owner or a minter can mint new bonds
+        self.assert_minter();
+
+        // measure initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        // mint the next token of the series
+        let token_id = self.internal_mint_series(&series_id, &receiver_id);
+
+        // contruct the mint log as per the events standard
+        let nft_mint_log: EventLog = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftMint(vec![NftMintLog {
+                // Owner of the token
+                owner_id: receiver_id.to_string(),
+                // vector of token IDs that were minted
+                token_ids: vec![token_id.clone()],
+                // An optional memo to include
+                memo: None,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&nft_mint_log.to_string());
+
+        // calculate the required storage which was the used - initial
+        let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
+
+        // take the storage from the prepaid balance of the user, or refund any excess storage if the user attached too much
+        self.internal_pay_storage(required_storage_in_bytes);
+
+        token_id
+    }
+
+    //Query for the bond series on the contract using pagination
+    pub fn nft_series(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<BondSeries> {
+        // where to start pagination - if we have a from_index, we'll use that - otherwise we start from 0
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.series_by_id.values()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    //Query for a specific bond series
+    pub fn nft_series_info(&self, series_id: String) -> Option<BondSeries> {
+        self.series_by_id.get(&series_id)
+    }
+
+    //Query for the number of tokens that can still be minted into a series
+    pub fn nft_series_remaining_supply(&self, series_id: String) -> u64 {
+        let series = self.series_by_id.get(&series_id).expect("No series");
+        series.max_supply - series.minted_count
+    }
+
+    //get the total supply of NFTs for a given series
+    pub fn nft_supply_for_series(&self, series_id: String) -> U128 {
+        // if there is some set of tokens, we'll return the length as a U128
+        if let Some(tokens_for_series_set) = self.tokens_per_series.get(&series_id) {
+            U128(tokens_for_series_set.len() as u128)
+        } else {
+            // If there isn't a set of tokens for the passed in series ID, we'll return 0
+            U128(0)
+        }
+    }
+
+    //Query for all the tokens of a series using pagination
+    pub fn nft_tokens_for_series(
+        &self,
+        series_id: String,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonToken> {
+        // get the set of tokens for the passed in series
+        let tokens = if let Some(tokens_for_series_set) = self.tokens_per_series.get(&series_id) {
+            tokens_for_series_set
+        } else {
+            return vec![];
+        };
+
+        // where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        tokens.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .map(|token_id| self.nft_token(token_id).unwrap())
+            .collect()
+    }
+}
+
+impl Contract {
+    // mint the next token of a series. Logging and storage payment is left to the caller
+    pub(crate) fn internal_mint_series(&mut self, series_id: &String, receiver_id: &AccountId) -> TokenId {
+        let mut series = self.series_by_id.get(series_id).expect("No series");
+
+        // make sure the series isn't sold out
+        assert!(series.minted_count < series.max_supply, "Series max supply reached");
+        series.minted_count += 1;
+
+        // the token IDs of a series are sequential, starting at 1
+        let token_id = format!("{}{}{}", series_id, SERIES_DELIMITER, series.minted_count);
+
+        // fill in the metadata template for the token
+        let mut metadata = series.metadata.clone();
+        metadata.title = metadata.title.map(|title| format!("{} #{}", title, series.minted_count));
+        metadata.copies = Some(series.max_supply);
+        metadata.issued_at = Some(current_timestamp_ms());
+
+        self.internal_mint(
+            &token_id,
+            &metadata,
+            receiver_id,
+            series.royalty.clone(),
+            Some(series.bond_terms.clone()),
+            Some(series_id.clone())
+        );
+
+        // save the updated minted count
+        self.series_by_id.insert(series_id, &series);
+
+        token_id
+    }
+}
@@ -0,0 +1,189 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /*
+        Take a snapshot of the ownership of every token, ex. at the record date of a coupon or a vote - caller has to be contract owner.
+        Nothing is copied when the snapshot is taken. The ownership is copied on the first change after the snapshot.
+        Only the latest MAX_RETAINED_SNAPSHOTS snapshots can be queried, so the copies stay bounded for every token and account.
+    */
+    pub fn take_snapshot(&mut self, memo: Option<String>) -> u64 {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // the snapshot ID is the index in the vector of snapshots
+        let snapshot_id = self.snapshots.len();
+        let snapshot = Snapshot {
+            snapshot_id,
+            taken_at: current_timestamp_ms(),
+            block_height: env::block_height(),
+        };
+        self.snapshots.push(&snapshot);
+
+        // construct the take snapshot log
+        let take_snapshot_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::TakeSnapshot(vec![TakeSnapshotLog {
+                snapshot_id,
+                taken_at: snapshot.taken_at,
+                block_height: snapshot.block_height,
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&take_snapshot_log.to_string());
+
+        snapshot_id
+    }
+
+    //Query for the snapshots using pagination
+    pub fn snapshots(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Snapshot> {
+        // where to start pagination - if we have a from_index, we'll use that - otherwise we start from 0
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.snapshots.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    //Query for the owner of a token when a snapshot was taken. Returns None if the token didn't exist back then or was burned since
+    pub fn owner_at(&self, snapshot_id: u64, token_id: TokenId) -> Option<AccountId> {
+        self.internal_assert_snapshot_retained(snapshot_id);
+
+        // the first change after the snapshot copied the owner at the snapshot
+        let checkpoint = self.token_checkpoints.get(&token_id).and_then(|checkpoints| {
+            checkpoints.into_iter().find(|checkpoint| checkpoint.snapshot_id >= snapshot_id)
+        });
+
+        match checkpoint {
+            Some(checkpoint) => checkpoint.owner_id,
+            // if the token didn't change since the snapshot, the current owner is the owner at the snapshot
+            None => self.tokens_by_id.get(&token_id).map(|token| token.owner_id),
+        }
+    }
+
+    //Query for the number of tokens an account held when a snapshot was taken, not counting tokens that were burned since
+    pub fn supply_for_owner_at(&self, snapshot_id: u64, account_id: AccountId) -> U128 {
+        U128(self.internal_tokens_for_owner_at(snapshot_id, &account_id).len() as u128)
+    }
+
+    //Query for the tokens an account held when a snapshot was taken using pagination
+    pub fn tokens_for_owner_at(
+        &self,
+        snapshot_id: u64,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<TokenId> {
+        // where to start pagination - if we have a from_index, we'll use that - otherwise we start from 0
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.internal_tokens_for_owner_at(snapshot_id, &account_id)
+            .into_iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+}
+
+impl Contract {
+    // make sure the snapshot exists and the ownership copied for it wasn't pruned yet
+    pub(crate) fn internal_assert_snapshot_retained(&self, snapshot_id: u64) {
+        assert!(snapshot_id < self.snapshots.len(), "No snapshot");
+        assert!(self.internal_is_snapshot_retained(snapshot_id), "Snapshot is no longer retained");
+    }
+
+    // check if the copies made for a snapshot can still be queried
+    pub(crate) fn internal_is_snapshot_retained(&self, snapshot_id: u64) -> bool {
+        snapshot_id + MAX_RETAINED_SNAPSHOTS >= self.snapshots.len()
+    }
+
+    // copy the owner of a token before it changes if this is the first change since the latest snapshot
+    pub(crate) fn internal_checkpoint_token(&mut self, token_id: &TokenId, owner_id: Option<&AccountId>) {
+        // there is nothing to copy if no snapshot was taken
+        let snapshot_id = if let Some(snapshot_id) = self.snapshots.len().checked_sub(1) {
+            snapshot_id
+        } else {
+            return;
+        };
+
+        let mut checkpoints = self.token_checkpoints.get(token_id).unwrap_or_default();
+        if checkpoints.last().map_or(true, |checkpoint| checkpoint.snapshot_id < snapshot_id) {
+            // the copies made for snapshots that can't be queried anymore are pruned
+            checkpoints.retain(|checkpoint| self.internal_is_snapshot_retained(checkpoint.snapshot_id));
+            checkpoints.push(TokenCheckpoint {
+                snapshot_id,
+                owner_id: owner_id.cloned(),
+            });
+            self.token_checkpoints.insert(token_id, &checkpoints);
+        }
+    }
+
+    // copy the tokens of an owner before they change if this is the first change since the latest snapshot
+    pub(crate) fn internal_checkpoint_owner(&mut self, account_id: &AccountId) {
+        // there is nothing to copy if no snapshot was taken
+        let snapshot_id = if let Some(snapshot_id) = self.snapshots.len().checked_sub(1) {
+            snapshot_id
+        } else {
+            return;
+        };
+
+        let mut checkpoints = self.owner_checkpoints.get(account_id).unwrap_or_default();
+        if checkpoints.last().map_or(true, |checkpoint| checkpoint.snapshot_id < snapshot_id) {
+            // the copies made for snapshots that can't be queried anymore are pruned
+            checkpoints.retain(|checkpoint| self.internal_is_snapshot_retained(checkpoint.snapshot_id));
+            let token_ids = self.tokens_per_owner
+                .get(account_id)
+                .map(|tokens_set| tokens_set.to_vec())
+                .unwrap_or_default();
+            checkpoints.push(OwnerCheckpoint {
+                snapshot_id,
+                token_ids,
+            });
+            self.owner_checkpoints.insert(account_id, &checkpoints);
+        }
+    }
+
+    // remove the copies of a burned token, including the token in the copies of the accounts that held it
+    pub(crate) fn internal_clear_checkpoints(&mut self, token_id: &TokenId, owner_id: &AccountId) {
+        let checkpoints = self.token_checkpoints.remove(token_id).unwrap_or_default();
+
+        // the accounts that held the token at a snapshot are the copied owners, or the owner if it didn't change since
+        let mut holder_ids: Vec<AccountId> = checkpoints.into_iter().filter_map(|checkpoint| checkpoint.owner_id).collect();
+        holder_ids.push(owner_id.clone());
+        holder_ids.sort();
+        holder_ids.dedup();
+
+        for holder_id in holder_ids {
+            if let Some(mut owner_checkpoints) = self.owner_checkpoints.get(&holder_id) {
+                for checkpoint in owner_checkpoints.iter_mut() {
+                    checkpoint.token_ids.retain(|id| id != token_id);
+                }
+                self.owner_checkpoints.insert(&holder_id, &owner_checkpoints);
+            }
+        }
+    }
+
+    // get the tokens an account held when a snapshot was taken
+    pub(crate) fn internal_tokens_for_owner_at(&self, snapshot_id: u64, account_id: &AccountId) -> Vec<TokenId> {
+        self.internal_assert_snapshot_retained(snapshot_id);
+
+        // the first change after the snapshot copied the tokens at the snapshot
+        let checkpoint = self.owner_checkpoints.get(account_id).and_then(|checkpoints| {
+            checkpoints.into_iter().find(|checkpoint| checkpoint.snapshot_id >= snapshot_id)
+        });
+
+        match checkpoint {
+            Some(checkpoint) => checkpoint.token_ids,
+            // if the tokens didn't change since the snapshot, the current tokens are the tokens at the snapshot
+            None => self.tokens_per_owner
+                .get(account_id)
+                .map(|tokens_set| tokens_set.to_vec())
+                .unwrap_or_default(),
+        }
+    }
+}
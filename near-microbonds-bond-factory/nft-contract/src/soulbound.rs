@@ -0,0 +1,32 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /*
+        Make the bonds of the contract non-transferable or transferable again - caller has to be contract owner.
+        The mode can only be changed before the first bond is minted.
+    */
+    pub fn set_soulbound(&mut self, soulbound: bool, memo: Option<String>) {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // holders bought their bonds under the mode that was set, so it can't change afterwards
+        assert_eq!(self.next_mint_index, 0, "Cannot change the transfer mode after the first mint");
+
+        self.soulbound = soulbound;
+
+        // construct the set soulbound log
+        let set_soulbound_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::SetSoulbound(vec![SetSoulboundLog {
+                soulbound,
+                // an optional memo to include
+                memo,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&set_soulbound_log.to_string());
+    }
+}
@@ -0,0 +1,145 @@
+use near_contract_standards::non_fungible_token::refund_deposit;
+use near_contract_standards::storage_management::{StorageBalance, StorageBalanceBounds, StorageManagement};
+use near_sdk::assert_one_yocto;
+
+use crate::*;
+
+/*
+    The number of bytes needed to store the storage balance of an account.
+    It covers the storage record overhead, the collection prefix, the longest possible account ID and the balance.
+*/
+pub const STORAGE_REGISTRATION_BYTES: u64 = 40 + 1 + 4 + 64 + 16;
+
+#[near_bindgen]
+impl StorageManagement for Contract {
+    /*
+        Prepay storage for an account so minting and approving don't need an attached deposit.
+        If no account ID is passed in, the deposit is added to the balance of the caller.
+    */
+    #[payable]
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let amount = env::attached_deposit();
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let min_balance = storage_registration_cost();
+
+        if let Some(balance) = self.storage_deposits.get(&account_id) {
+            if registration_only.unwrap_or(false) {
+                // the account is registered already so we refund the whole deposit
+                if amount > 0 {
+                    Promise::new(env::predecessor_account_id()).transfer(amount);
+                }
+            } else {
+                self.storage_deposits.insert(&account_id, &(balance + amount));
+            }
+        } else {
+            assert!(
+                amount >= min_balance,
+                "The attached deposit is less than the minimum storage balance of {}", min_balance
+            );
+
+            if registration_only.unwrap_or(false) {
+                // only keep what is needed to register the account and refund the rest
+                self.storage_deposits.insert(&account_id, &min_balance);
+                let refund = amount - min_balance;
+                if refund > 0 {
+                    Promise::new(env::predecessor_account_id()).transfer(refund);
+                }
+            } else {
+                self.storage_deposits.insert(&account_id, &amount);
+            }
+        }
+
+        self.storage_balance_of(account_id).unwrap()
+    }
+
+    //Withdraw the available storage balance of the caller. If no amount is passed in, everything available is withdrawn
+    #[payable]
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        // assert that the user attached exactly 1 yoctoNEAR for security reasons
+        assert_one_yocto();
+
+        let account_id = env::predecessor_account_id();
+        let balance = self.storage_deposits.get(&account_id).expect("Account is not registered");
+        let available = balance - storage_registration_cost();
+
+        let amount = amount.map(|amount| amount.0).unwrap_or(available);
+        assert!(amount <= available, "Amount exceeds the available storage balance");
+
+        if amount > 0 {
+            self.storage_deposits.insert(&account_id, &(balance - amount));
+            Promise::new(account_id.clone()).transfer(amount);
+        }
+
+        self.storage_balance_of(account_id).unwrap()
+    }
+
+    /*
+        Unregister the caller and refund their whole storage balance. Returns false if the caller wasn't registered.
+        The storage of tokens and approvals is paid for when they are created, so unregistering never has to be forced.
+    */
+    #[payable]
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        // assert that the user attached exactly 1 yoctoNEAR for security reasons
+        assert_one_yocto();
+        let _ = force;
+
+        let account_id = env::predecessor_account_id();
+        if let Some(balance) = self.storage_deposits.remove(&account_id) {
+            Promise::new(account_id).transfer(balance);
+            true
+        } else {
+            false
+        }
+    }
+
+    //Query for the minimum storage balance needed to register an account
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: U128(storage_registration_cost()),
+            max: None,
+        }
+    }
+
+    //Query for the storage balance of an account, if it is registered
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.storage_deposits.get(&account_id).map(|balance| StorageBalance {
+            total: U128(balance),
+            available: U128(balance - storage_registration_cost()),
+        })
+    }
+}
+
+impl Contract {
+    /*
+        Pay for the storage used by the caller. If the prepaid storage balance of the caller covers it, the cost is
+        taken from there and the attached deposit is refunded. Otherwise the attached deposit has to cover the storage.
+    */
+    pub(crate) fn internal_pay_storage(&mut self, storage_used: u64) {
+        let account_id = env::predecessor_account_id();
+        let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
+
+        if let Some(balance) = self.storage_deposits.get(&account_id) {
+            if balance - storage_registration_cost() >= required_cost {
+                self.storage_deposits.insert(&account_id, &(balance - required_cost));
+
+                let attached_deposit = env::attached_deposit();
+                if attached_deposit > 1 {
+                    Promise::new(account_id).transfer(attached_deposit);
+                }
+                return;
+            }
+        }
+
+        // refund any excess storage attached by the user. If the user didn't attach enough, panic
+        refund_deposit(storage_used);
+    }
+}
+
+// the storage balance an account needs to be registered
+pub(crate) fn storage_registration_cost() -> Balance {
+    env::storage_byte_cost() * Balance::from(STORAGE_REGISTRATION_BYTES)
+}
@@ -1,17 +1,22 @@
 /* unit tests */
 #[cfg(test)]
 use crate::Contract;
-use crate::TokenMetadata;
+use crate::{compute_payout, BatchMintEntry, BondTerms, CampaignStatus, CouponEntitlement, DayCountConvention, DEFAULT_HISTORY_CAP, MAX_RETAINED_SNAPSHOTS, PlatformFee, SaleConfig, TokenMetadata, TransferRegistry};
 use crate::approval::NonFungibleTokenCore;
+use crate::cashflow::add_months;
+use crate::upgrade::{write_schema_version, SCHEMA_VERSION};
+use near_sdk::borsh::BorshSerialize;
 use near_sdk::json_types::{U128, U64};
 use near_sdk::test_utils::{accounts, VMContextBuilder};
 use near_sdk::testing_env;
-use near_sdk::{env, AccountId};
+use near_sdk::{env, AccountId, Gas, PromiseResult, RuntimeFeesConfig, VMConfig};
 
 use std::collections::HashMap;
 
 const MINT_STORAGE_COST: u128 = 100_000_000_000_000_000_000_000;
 const MIN_REQUIRED_APPROVAL_YOCTO: u128 = 170000000000000000000;
+const COUPON_DEPOSIT: u128 = 10_000_000_000_000_000_000_000_000;
+const CLAIM_STORAGE_COST: u128 = 10_000_000_000_000_000_000_000;
 
 fn get_context(predecessor: AccountId) -> VMContextBuilder {
     let mut builder = VMContextBuilder::new();
@@ -36,6 +41,16 @@ fn sample_token_metadata() -> TokenMetadata {
     }
 }
 
+fn sample_bond_terms() -> BondTerms {
+    BondTerms {
+        face_value: U128(1_000_000_000_000_000_000_000_000),
+        coupon_rate: 500,
+        coupon_frequency: 2,
+        issue_date: 1_672_531_200_000,
+        maturity_date: 1_767_225_600_000,
+    }
+}
+
 #[test]
 #[should_panic(expected = "The contract is not initialized")]
 fn test_default() {
@@ -66,7 +81,7 @@ fn test_mint_nft() {
         .build());
     let token_metadata: TokenMetadata = sample_token_metadata();
     let token_id = "0".to_string();
-    contract.nft_mint(token_id.clone(), token_metadata, accounts(0), None);
+    contract.nft_mint(token_id.clone(), token_metadata, accounts(0), None, None);
     let contract_nft_tokens = contract.nft_tokens(Some(U128(0)), None);
     assert_eq!(contract_nft_tokens.len(), 1);
 
@@ -99,7 +114,7 @@ fn test_internal_transfer() {
         .predecessor_account_id(accounts(0))
         .build());
     let token_id = "0".to_string();
-    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None);
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None, None);
 
     testing_env!(context
         .storage_usage(env::storage_usage())
@@ -112,6 +127,7 @@ fn test_internal_transfer() {
         &token_id.clone(),
         None,
         None,
+        true,
     );
 
     testing_env!(context
@@ -151,14 +167,14 @@ fn test_nft_approve() {
         .predecessor_account_id(accounts(0))
         .build());
     let token_id = "0".to_string();
-    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None);
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None, None);
 
     testing_env!(context
         .storage_usage(env::storage_usage())
         .attached_deposit(MIN_REQUIRED_APPROVAL_YOCTO)
         .predecessor_account_id(accounts(0))
         .build());
-    contract.nft_approve(token_id.clone(), accounts(1), None);
+    contract.nft_approve(token_id.clone(), accounts(1), None, None);
 
     testing_env!(context
         .storage_usage(env::storage_usage())
@@ -181,7 +197,7 @@ fn test_nft_revoke() {
         .predecessor_account_id(accounts(0))
         .build());
     let token_id = "0".to_string();
-    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None);
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None, None);
 
     // alice approves bob
     testing_env!(context
@@ -189,7 +205,7 @@ fn test_nft_revoke() {
         .attached_deposit(MIN_REQUIRED_APPROVAL_YOCTO)
         .predecessor_account_id(accounts(0))
         .build());
-    contract.nft_approve(token_id.clone(), accounts(1), None);
+    contract.nft_approve(token_id.clone(), accounts(1), None, None);
 
     // alice revokes bob
     testing_env!(context
@@ -219,7 +235,7 @@ fn test_revoke_all() {
         .predecessor_account_id(accounts(0))
         .build());
     let token_id = "0".to_string();
-    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None);
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None, None);
 
     // alice approves bob
     testing_env!(context
@@ -227,7 +243,7 @@ fn test_revoke_all() {
         .attached_deposit(MIN_REQUIRED_APPROVAL_YOCTO)
         .predecessor_account_id(accounts(0))
         .build());
-    contract.nft_approve(token_id.clone(), accounts(1), None);
+    contract.nft_approve(token_id.clone(), accounts(1), None, None);
 
     // alice revokes bob
     testing_env!(context
@@ -257,7 +273,7 @@ fn test_internal_remove_token_from_owner() {
         .predecessor_account_id(accounts(0))
         .build());
     let token_id = "0".to_string();
-    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None);
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None, None);
 
     let contract_nft_tokens_before = contract.nft_tokens_for_owner(accounts(0), None, None);
     assert_eq!(contract_nft_tokens_before.len(), 1);
@@ -280,7 +296,7 @@ fn test_nft_payout() {
         .predecessor_account_id(accounts(0))
         .build());
     let token_id = "0".to_string();
-    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None);
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None, None);
 
     // alice approves bob
     testing_env!(context
@@ -288,7 +304,7 @@ fn test_nft_payout() {
         .attached_deposit(MIN_REQUIRED_APPROVAL_YOCTO)
         .predecessor_account_id(accounts(0))
         .build());
-    contract.nft_approve(token_id.clone(), accounts(1), None);
+    contract.nft_approve(token_id.clone(), accounts(1), None, None);
 
     let payout = contract.nft_payout(token_id.clone(), U128(10), 1);
     let expected = HashMap::from([(accounts(0), U128(10))]);
@@ -307,8 +323,2074 @@ fn test_nft_total_supply() {
         .predecessor_account_id(accounts(0))
         .build());
     let token_id = "0".to_string();
-    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None);
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None, None);
 
     let total_supply = contract.nft_total_supply();
     assert_eq!(total_supply, U128(1));
-}
\ No newline at end of file
+}
+#[test]
+fn test_mint_nft_by_minter() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    // owner adds bob as a minter
+    contract.add_minter(accounts(1), None);
+    assert!(contract.is_minter(accounts(1)));
+    assert_eq!(contract.get_minters(None, None), vec![accounts(1)]);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(1))
+        .build());
+    let token_id = "0".to_string();
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(2), None, None);
+
+    let tokens = contract.nft_tokens_for_owner(accounts(2), None, None);
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].token_id, token_id);
+}
+
+#[test]
+#[should_panic(expected = "Caller not minter")]
+fn test_mint_nft_non_minter() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, None);
+}
+
+#[test]
+#[should_panic(expected = "Caller not minter")]
+fn test_mint_nft_removed_minter() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    // owner adds and then removes bob as a minter
+    contract.add_minter(accounts(1), None);
+    contract.remove_minter(accounts(1), None);
+    assert!(!contract.is_minter(accounts(1)));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, None);
+}
+
+#[test]
+#[should_panic(expected = "Caller not owner")]
+fn test_add_minter_non_owner() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.add_minter(accounts(1), None);
+}
+
+#[test]
+fn test_mint_nft_with_bond_terms() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let token_id = "0".to_string();
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(1), None, Some(sample_bond_terms()));
+
+    let token = contract.nft_token(token_id.clone()).unwrap();
+    assert_eq!(token.bond_terms, Some(sample_bond_terms()));
+    assert_eq!(contract.nft_bond_terms(token_id), Some(sample_bond_terms()));
+}
+
+#[test]
+#[should_panic(expected = "Coupon frequency must be one of")]
+fn test_mint_nft_with_invalid_bond_terms() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let bond_terms = BondTerms {
+        coupon_frequency: 3,
+        ..sample_bond_terms()
+    };
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, Some(bond_terms));
+}
+
+#[test]
+#[should_panic(expected = "Maturity date must be after the issue date")]
+fn test_mint_nft_with_maturity_before_issue() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let bond_terms = BondTerms {
+        maturity_date: sample_bond_terms().issue_date,
+        ..sample_bond_terms()
+    };
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, Some(bond_terms));
+}
+
+#[test]
+fn test_coupon_deposit_and_claim() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, None);
+    contract.nft_mint("1".to_string(), sample_token_metadata(), accounts(2), None, None);
+
+    // owner deposits a coupon for the two tokens
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(COUPON_DEPOSIT)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let period_id = contract.coupon_deposit(None);
+    assert_eq!(period_id, 0);
+
+    // a token minted after the deposit is not eligible
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("2".to_string(), sample_token_metadata(), accounts(1), None, None);
+    assert_eq!(contract.coupon_entitlements_for_token("2".to_string()), vec![]);
+    assert_eq!(
+        contract.coupon_entitlements_for_token("0".to_string()),
+        vec![CouponEntitlement { period_id: 0, amount: U128(COUPON_DEPOSIT / 2) }]
+    );
+    assert_eq!(contract.coupon_outstanding_for_owner(accounts(1)), U128(COUPON_DEPOSIT / 2));
+
+    // bob claims the coupon for his token
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(CLAIM_STORAGE_COST)
+        .predecessor_account_id(accounts(1))
+        .build());
+    let claimed = contract.coupon_claim("0".to_string(), None);
+    assert_eq!(claimed, U128(COUPON_DEPOSIT / 2));
+    assert_eq!(contract.coupon_entitlements_for_token("0".to_string()), vec![]);
+    assert_eq!(contract.coupon_outstanding_for_owner(accounts(1)), U128(0));
+    assert_eq!(contract.coupon_periods(None, None)[0].claimed_count, 1);
+}
+
+#[test]
+#[should_panic(expected = "No coupon to claim")]
+fn test_coupon_claim_twice() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(COUPON_DEPOSIT)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.coupon_deposit(None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(CLAIM_STORAGE_COST)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.coupon_claim("0".to_string(), Some(0));
+    contract.coupon_claim("0".to_string(), Some(0));
+}
+
+#[test]
+#[should_panic(expected = "Predecessor must be token owner")]
+fn test_coupon_claim_non_owner() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(COUPON_DEPOSIT)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.coupon_deposit(None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(CLAIM_STORAGE_COST)
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.coupon_claim("0".to_string(), None);
+}
+
+#[test]
+fn test_nft_redeem() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let token_id = "0".to_string();
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(1), None, Some(sample_bond_terms()));
+
+    // owner funds the principal
+    let face_value = sample_bond_terms().face_value.0;
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(face_value)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.principal_deposit(None);
+    assert_eq!(contract.principal_balance(), U128(face_value));
+
+    // bob redeems the bond after maturity
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .block_timestamp(sample_bond_terms().maturity_date * 1_000_000)
+        .predecessor_account_id(accounts(1))
+        .build());
+    let amount = contract.nft_redeem(token_id.clone(), None, None);
+    // the storage of the token was paid by the minter so it isn't paid out to bob
+    assert_eq!(amount, U128(face_value));
+
+    assert_eq!(contract.principal_balance(), U128(0));
+    assert_eq!(contract.nft_total_supply(), U128(0));
+    assert_eq!(contract.nft_tokens_for_owner(accounts(1), None, None).len(), 0);
+    assert_eq!(contract.nft_bond_terms(token_id), None);
+}
+
+#[test]
+#[should_panic(expected = "Bond has not matured yet")]
+fn test_nft_redeem_before_maturity() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let token_id = "0".to_string();
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(1), None, Some(sample_bond_terms()));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(sample_bond_terms().face_value.0)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.principal_deposit(None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .block_timestamp(sample_bond_terms().issue_date * 1_000_000)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_redeem(token_id, None, None);
+}
+
+#[test]
+#[should_panic(expected = "Account bob is frozen")]
+fn test_nft_redeem_frozen_account() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let token_id = "0".to_string();
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(1), None, Some(sample_bond_terms()));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(sample_bond_terms().face_value.0)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.principal_deposit(None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(0)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.freeze_account(accounts(1), None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .block_timestamp(sample_bond_terms().maturity_date * 1_000_000)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_redeem(token_id, None, None);
+}
+
+#[test]
+#[should_panic(expected = "Not enough principal funded to redeem the bond")]
+fn test_nft_redeem_unfunded() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let token_id = "0".to_string();
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(1), None, Some(sample_bond_terms()));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .block_timestamp(sample_bond_terms().maturity_date * 1_000_000)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_redeem(token_id, None, None);
+}
+
+#[test]
+fn test_mint_series() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.create_series("A".to_string(), sample_token_metadata(), sample_bond_terms(), 2, None, None);
+    assert_eq!(contract.nft_series(None, None).len(), 1);
+    assert_eq!(contract.nft_series_remaining_supply("A".to_string()), 2);
+
+    let first_token_id = contract.nft_mint_series("A".to_string(), accounts(1));
+    let second_token_id = contract.nft_mint_series("A".to_string(), accounts(2));
+    assert_eq!(first_token_id, "A:1");
+    assert_eq!(second_token_id, "A:2");
+    assert_eq!(contract.nft_series_remaining_supply("A".to_string()), 0);
+    assert_eq!(contract.nft_supply_for_series("A".to_string()), U128(2));
+
+    let tokens = contract.nft_tokens_for_series("A".to_string(), None, None);
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens[0].series_id, Some("A".to_string()));
+    assert_eq!(tokens[0].bond_terms, Some(sample_bond_terms()));
+    assert_eq!(tokens[0].metadata.title, Some("Olympus Mons #1".to_string()));
+    assert_eq!(tokens[0].metadata.copies, Some(2));
+}
+
+#[test]
+#[should_panic(expected = "Token ID cannot contain ':'")]
+fn test_mint_series_token_id() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.create_series("A".to_string(), sample_token_metadata(), sample_bond_terms(), 2, None, None);
+    contract.nft_mint("A:1".to_string(), sample_token_metadata(), accounts(1), None, None);
+}
+
+#[test]
+#[should_panic(expected = "Series max supply reached")]
+fn test_mint_series_above_max_supply() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.create_series("A".to_string(), sample_token_metadata(), sample_bond_terms(), 1, None, None);
+    contract.nft_mint_series("A".to_string(), accounts(1));
+    contract.nft_mint_series("A".to_string(), accounts(1));
+}
+
+#[test]
+#[should_panic(expected = "Caller not owner")]
+fn test_create_series_non_owner() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.create_series("A".to_string(), sample_token_metadata(), sample_bond_terms(), 1, None, None);
+}
+
+#[test]
+#[should_panic(expected = "Transfers are locked for series A")]
+fn test_transfer_locked_series() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.create_series("A".to_string(), sample_token_metadata(), sample_bond_terms(), 1, None, None);
+    let token_id = contract.nft_mint_series("A".to_string(), accounts(1));
+    contract.lock_series("A".to_string(), None);
+    assert_eq!(
+        contract.nft_transfer_restriction(token_id.clone()),
+        Some("Transfers are locked for series A".to_string())
+    );
+
+    contract.internal_transfer(&accounts(1), &accounts(2), &token_id, None, None, true);
+}
+
+#[test]
+fn test_transfer_unlocked_series() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.create_series("A".to_string(), sample_token_metadata(), sample_bond_terms(), 1, None, None);
+    let token_id = contract.nft_mint_series("A".to_string(), accounts(1));
+    contract.lock_series("A".to_string(), None);
+    contract.unlock_series("A".to_string(), None);
+    assert_eq!(contract.nft_transfer_restriction(token_id.clone()), None);
+
+    contract.internal_transfer(&accounts(1), &accounts(2), &token_id, None, None, true);
+    assert_eq!(contract.nft_tokens_for_owner(accounts(2), None, None).len(), 1);
+}
+
+#[test]
+fn test_transfer_lockup() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let token_id = "0".to_string();
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(1), None, None);
+    let lockup_id = contract.add_transfer_lockup(None, 1_000, 2_000, Some("Record date".to_string()));
+
+    // the lockup doesn't apply before it starts
+    assert_eq!(contract.nft_transfer_restriction(token_id.clone()), None);
+
+    // the lockup applies while it is active
+    testing_env!(context.block_timestamp(1_500 * 1_000_000).build());
+    assert_eq!(
+        contract.nft_transfer_restriction(token_id.clone()),
+        Some("Transfers are locked until 2000: Record date".to_string())
+    );
+
+    // the lockup no longer applies once it is removed
+    contract.remove_transfer_lockup(lockup_id, None);
+    assert_eq!(contract.nft_transfer_restriction(token_id), None);
+}
+
+#[test]
+#[should_panic(expected = "Token cannot be transferred before 1000")]
+fn test_transfer_before_starts_at() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let token_id = "0".to_string();
+    let metadata = TokenMetadata {
+        starts_at: Some(1_000),
+        ..sample_token_metadata()
+    };
+    contract.nft_mint(token_id.clone(), metadata, accounts(1), None, None);
+
+    contract.internal_transfer(&accounts(1), &accounts(2), &token_id, None, None, true);
+}
+
+fn sample_transfer_registry() -> TransferRegistry {
+    TransferRegistry {
+        registry_id: accounts(4),
+        municipality_id: "municipality1".to_string(),
+    }
+}
+
+#[test]
+fn test_transfer_with_registry_waits_for_check() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let token_id = "0".to_string();
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None, None);
+    contract.set_transfer_registry(Some(sample_transfer_registry()), None);
+    assert_eq!(contract.transfer_registry(), Some(sample_transfer_registry()));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_transfer(accounts(1), token_id.clone(), None, None);
+
+    // the token is only transferred once the receiver has been checked
+    assert_eq!(contract.nft_token(token_id).unwrap().owner_id, accounts(0));
+}
+
+#[test]
+fn test_resolve_receiver_check_registered() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let token_id = "0".to_string();
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None, None);
+    contract.set_transfer_registry(Some(sample_transfer_registry()), None);
+
+    // the registry returned that the receiver is registered
+    testing_env!(
+        context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(0)
+            .predecessor_account_id(env::current_account_id())
+            .build(),
+        VMConfig::test(),
+        RuntimeFeesConfig::test(),
+        HashMap::default(),
+        vec![PromiseResult::Successful(serde_json::to_vec(&true).unwrap())]
+    );
+    contract.nft_resolve_receiver_check(accounts(0), accounts(1), token_id.clone(), None, None, None);
+
+    assert_eq!(contract.nft_token(token_id).unwrap().owner_id, accounts(1));
+}
+
+#[test]
+#[should_panic(expected = "Receiver is not registered in the municipality")]
+fn test_resolve_receiver_check_not_registered() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let token_id = "0".to_string();
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(0), None, None);
+    contract.set_transfer_registry(Some(sample_transfer_registry()), None);
+
+    // the registry returned that the receiver is not registered
+    testing_env!(
+        context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(0)
+            .predecessor_account_id(env::current_account_id())
+            .build(),
+        VMConfig::test(),
+        RuntimeFeesConfig::test(),
+        HashMap::default(),
+        vec![PromiseResult::Successful(serde_json::to_vec(&false).unwrap())]
+    );
+    contract.nft_resolve_receiver_check(accounts(0), accounts(1), token_id, None, None, None);
+}
+
+#[test]
+fn test_batch_mint() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST * 3)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let entries = vec![
+        BatchMintEntry {
+            token_id: "0".to_string(),
+            metadata: sample_token_metadata(),
+            receiver_id: accounts(1),
+            bond_terms: Some(sample_bond_terms()),
+        },
+        BatchMintEntry {
+            token_id: "1".to_string(),
+            metadata: sample_token_metadata(),
+            receiver_id: accounts(2),
+            bond_terms: Some(sample_bond_terms()),
+        },
+        BatchMintEntry {
+            token_id: "2".to_string(),
+            metadata: sample_token_metadata(),
+            receiver_id: accounts(1),
+            bond_terms: None,
+        },
+    ];
+    contract.nft_batch_mint(entries, None);
+
+    assert_eq!(contract.nft_total_supply(), U128(3));
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), U128(2));
+    assert_eq!(contract.nft_bond_terms("1".to_string()), Some(sample_bond_terms()));
+    assert_eq!(contract.nft_bond_terms("2".to_string()), None);
+
+    // the minted tokens are grouped by owner in a single event
+    let logs = near_sdk::test_utils::get_logs();
+    assert_eq!(logs.len(), 1);
+    assert!(logs[0].contains(r#"{"owner_id":"bob","token_ids":["0","2"]}"#));
+    assert!(logs[0].contains(r#"{"owner_id":"charlie","token_ids":["1"]}"#));
+}
+
+#[test]
+#[should_panic(expected = "Token ID cannot contain ':'")]
+fn test_batch_mint_series_token_id() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_batch_mint(vec![BatchMintEntry {
+        token_id: "A:1".to_string(),
+        metadata: sample_token_metadata(),
+        receiver_id: accounts(1),
+        bond_terms: None,
+    }], None);
+}
+
+#[test]
+#[should_panic(expected = "Token already exists")]
+fn test_batch_mint_duplicate_token() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST * 2)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let entry = BatchMintEntry {
+        token_id: "0".to_string(),
+        metadata: sample_token_metadata(),
+        receiver_id: accounts(1),
+        bond_terms: None,
+    };
+    contract.nft_batch_mint(vec![entry.clone(), entry], None);
+}
+
+#[test]
+#[should_panic(expected = "Cannot mint more than 50 tokens in a batch")]
+fn test_batch_mint_too_large() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let entries = (0..51)
+        .map(|i| BatchMintEntry {
+            token_id: i.to_string(),
+            metadata: sample_token_metadata(),
+            receiver_id: accounts(1),
+            bond_terms: None,
+        })
+        .collect();
+    contract.nft_batch_mint(entries, None);
+}
+
+const BOND_PRICE: u128 = 1_000_000_000_000_000_000_000_000;
+
+fn sample_sale_config() -> SaleConfig {
+    SaleConfig {
+        price: U128(BOND_PRICE),
+        starts_at: 1_672_531_200_000,
+        ends_at: 1_675_209_600_000,
+        max_per_buyer: Some(2),
+        max_total: Some(3),
+        treasury_id: accounts(3),
+        soft_cap: None,
+    }
+}
+
+// create series A and open its sale, then move the context into the sale as the passed in buyer
+fn setup_sale(context: &mut VMContextBuilder, buyer: AccountId, attached_deposit: u128) -> Contract {
+    setup_sale_with_config(context, buyer, attached_deposit, sample_sale_config())
+}
+
+fn setup_sale_with_config(
+    context: &mut VMContextBuilder,
+    buyer: AccountId,
+    attached_deposit: u128,
+    config: SaleConfig,
+) -> Contract {
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.create_series("A".to_string(), sample_token_metadata(), sample_bond_terms(), 10, None, None);
+    contract.configure_sale("A".to_string(), config, None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(attached_deposit)
+        .block_timestamp(sample_sale_config().starts_at * 1_000_000)
+        .predecessor_account_id(buyer)
+        .build());
+    contract
+}
+
+#[test]
+fn test_buy() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_sale(&mut context, accounts(1), BOND_PRICE * 2 + MINT_STORAGE_COST);
+
+    let token_ids = contract.buy("A".to_string(), 2);
+    assert_eq!(token_ids, vec!["A:1".to_string(), "A:2".to_string()]);
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), U128(2));
+    assert_eq!(contract.sale_info("A".to_string()).unwrap().sold_count, 2);
+    assert_eq!(contract.sale_purchased_by("A".to_string(), accounts(1)), 2);
+}
+
+#[test]
+#[should_panic(expected = "Purchase exceeds the per-buyer cap")]
+fn test_buy_above_per_buyer_cap() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_sale(&mut context, accounts(1), BOND_PRICE * 3 + MINT_STORAGE_COST);
+    contract.buy("A".to_string(), 3);
+}
+
+#[test]
+#[should_panic(expected = "Not enough bonds left in the sale")]
+fn test_buy_above_total_cap() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_sale(&mut context, accounts(1), BOND_PRICE * 2 + MINT_STORAGE_COST);
+    contract.buy("A".to_string(), 2);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.buy("A".to_string(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Must attach")]
+fn test_buy_underpaid() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_sale(&mut context, accounts(1), BOND_PRICE);
+    contract.buy("A".to_string(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Sale has ended")]
+fn test_buy_after_sale_ended() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_sale(&mut context, accounts(1), BOND_PRICE + MINT_STORAGE_COST);
+
+    testing_env!(context
+        .block_timestamp(sample_sale_config().ends_at * 1_000_000)
+        .build());
+    contract.buy("A".to_string(), 1);
+}
+
+// a sale of series A that only succeeds if 2 bonds are sold
+fn sample_campaign_config() -> SaleConfig {
+    SaleConfig {
+        soft_cap: Some(U128(BOND_PRICE * 2)),
+        ..sample_sale_config()
+    }
+}
+
+#[test]
+fn test_campaign_succeeded() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_sale_with_config(&mut context, accounts(1), BOND_PRICE * 2 + MINT_STORAGE_COST, sample_campaign_config());
+    contract.buy("A".to_string(), 2);
+    assert_eq!(contract.campaign_status("A".to_string()), Some(CampaignStatus::Funding));
+    assert_eq!(contract.campaign_raised("A".to_string()), U128(BOND_PRICE * 2));
+    assert_eq!(contract.campaign_contribution("A".to_string(), accounts(1)), U128(BOND_PRICE * 2));
+    assert!(contract.nft_transfer_restriction("A:1".to_string()).is_some());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(0)
+        .block_timestamp(sample_sale_config().ends_at * 1_000_000)
+        .predecessor_account_id(accounts(0))
+        .build());
+    assert_eq!(contract.campaign_status("A".to_string()), Some(CampaignStatus::Succeeded));
+    assert_eq!(contract.nft_transfer_restriction("A:1".to_string()), None);
+    assert_eq!(contract.campaign_withdraw("A".to_string()), U128(BOND_PRICE * 2));
+    assert!(contract.sale_info("A".to_string()).unwrap().withdrawn);
+}
+
+#[test]
+fn test_campaign_failed_refund() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_sale_with_config(&mut context, accounts(1), BOND_PRICE + MINT_STORAGE_COST, sample_campaign_config());
+    contract.buy("A".to_string(), 1);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(0)
+        .block_timestamp(sample_sale_config().ends_at * 1_000_000)
+        .predecessor_account_id(accounts(1))
+        .build());
+    assert_eq!(contract.campaign_status("A".to_string()), Some(CampaignStatus::Failed));
+    let refund = contract.campaign_refund("A".to_string());
+    assert!(refund.0 > BOND_PRICE);
+    assert_eq!(contract.nft_supply_for_owner(accounts(1)), U128(0));
+    assert_eq!(contract.campaign_contribution("A".to_string(), accounts(1)), U128(0));
+    assert_eq!(contract.campaign_raised("A".to_string()), U128(0));
+    assert_eq!(contract.sale_info("A".to_string()).unwrap().sold_count, 0);
+}
+
+#[test]
+fn test_campaign_refund_pays_unclaimed_coupons() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_sale_with_config(&mut context, accounts(1), BOND_PRICE + MINT_STORAGE_COST, sample_campaign_config());
+    contract.buy("A".to_string(), 1);
+
+    // a coupon is deposited while the campaign is still funding
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1_000)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.coupon_deposit(None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(0)
+        .block_timestamp(sample_sale_config().ends_at * 1_000_000)
+        .predecessor_account_id(accounts(1))
+        .build());
+    let refund = contract.campaign_refund("A".to_string());
+
+    // the coupon of the burned bond is paid out with the refund instead of being stuck in the contract
+    assert!(refund.0 >= BOND_PRICE + 1_000);
+    assert_eq!(contract.coupon_periods(None, None)[0].claimed_count, 1);
+    assert!(near_sdk::test_utils::get_logs().last().unwrap().contains("\"coupon\":\"1000\""));
+}
+
+#[test]
+fn test_campaign_refund_after_force_transfer() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    let config = SaleConfig {
+        soft_cap: Some(U128(BOND_PRICE * 3)),
+        ..sample_sale_config()
+    };
+    let mut contract = setup_sale_with_config(&mut context, accounts(1), BOND_PRICE * 2 + MINT_STORAGE_COST, config);
+    contract.buy("A".to_string(), 2);
+
+    // one of the bonds is recovered to another account while the campaign is funding
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(0)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_force_transfer("A:2".to_string(), accounts(2), "LOST_KEYS".to_string(), None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .block_timestamp(sample_sale_config().ends_at * 1_000_000)
+        .predecessor_account_id(accounts(1))
+        .build());
+    let refund = contract.campaign_refund("A".to_string());
+    assert!(refund.0 > BOND_PRICE && refund.0 < BOND_PRICE * 2);
+    assert!(contract.nft_token("A:1".to_string()).is_none());
+    assert_eq!(contract.nft_token("A:2".to_string()).unwrap().owner_id, accounts(2));
+    assert_eq!(contract.campaign_contribution("A".to_string(), accounts(1)), U128(BOND_PRICE));
+    assert_eq!(contract.campaign_raised("A".to_string()), U128(BOND_PRICE));
+    assert_eq!(contract.sale_info("A".to_string()).unwrap().sold_count, 1);
+}
+
+#[test]
+#[should_panic(expected = "Campaign did not fail")]
+fn test_campaign_refund_while_funding() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_sale_with_config(&mut context, accounts(1), BOND_PRICE + MINT_STORAGE_COST, sample_campaign_config());
+    contract.buy("A".to_string(), 1);
+    contract.campaign_refund("A".to_string());
+}
+
+#[test]
+#[should_panic(expected = "Campaign is still funding")]
+fn test_campaign_withdraw_while_funding() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_sale_with_config(&mut context, accounts(1), BOND_PRICE * 2 + MINT_STORAGE_COST, sample_campaign_config());
+    contract.buy("A".to_string(), 2);
+
+    testing_env!(context
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.campaign_withdraw("A".to_string());
+}
+
+#[test]
+#[should_panic(expected = "Cannot change the deadline or price of a campaign after bonds were sold")]
+fn test_campaign_extend_after_sold() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_sale_with_config(&mut context, accounts(1), BOND_PRICE + MINT_STORAGE_COST, sample_campaign_config());
+    contract.buy("A".to_string(), 1);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.configure_sale("A".to_string(), SaleConfig {
+        ends_at: sample_sale_config().ends_at * 2,
+        ..sample_campaign_config()
+    }, None);
+}
+
+#[test]
+#[should_panic(expected = "Royalties cannot add up to more than 10000 basis points")]
+fn test_mint_royalty_above_total() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let royalty = HashMap::from([(accounts(2), 6_000), (accounts(3), 5_000)]);
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), Some(royalty), None);
+}
+
+#[test]
+fn test_default_royalty() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.set_default_royalty(HashMap::from([(accounts(2), 500)]), None);
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, None);
+
+    assert_eq!(
+        contract.nft_royalty_split("0".to_string()),
+        HashMap::from([(accounts(2), 500), (accounts(1), 9_500)])
+    );
+}
+
+#[test]
+fn test_update_royalty() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), Some(HashMap::from([(accounts(2), 500)])), None);
+    contract.nft_update_royalty("0".to_string(), HashMap::from([(accounts(3), 1_000), (accounts(1), 200)]), None);
+
+    // the token owner gets their royalty on top of the rest of the payout
+    assert_eq!(
+        contract.nft_royalty_split("0".to_string()),
+        HashMap::from([(accounts(3), 1_000), (accounts(1), 9_000)])
+    );
+}
+
+#[test]
+#[should_panic(expected = "Caller not owner")]
+fn test_update_royalty_non_owner() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, None);
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_update_royalty("0".to_string(), HashMap::new(), None);
+}
+
+// get the payout as a map of plain balances so it's easy to compare
+fn payout_amounts(
+    royalty: &HashMap<AccountId, u32>,
+    owner_id: &AccountId,
+    platform_fee: Option<&PlatformFee>,
+    balance: u128,
+    max_len_payout: u32,
+) -> HashMap<AccountId, u128> {
+    compute_payout(royalty, owner_id, platform_fee, balance, max_len_payout)
+        .payout
+        .into_iter()
+        .map(|(account_id, amount)| (account_id, amount.0))
+        .collect()
+}
+
+#[test]
+fn test_payout_without_royalties() {
+    let payout = payout_amounts(&HashMap::new(), &accounts(1), None, 1_000, 1);
+    assert_eq!(payout, HashMap::from([(accounts(1), 1_000)]));
+}
+
+#[test]
+fn test_payout_with_royalties() {
+    let royalty = HashMap::from([(accounts(2), 1_000), (accounts(3), 500)]);
+    let payout = payout_amounts(&royalty, &accounts(1), None, 1_000, 3);
+    assert_eq!(payout, HashMap::from([(accounts(1), 850), (accounts(2), 100), (accounts(3), 50)]));
+}
+
+#[test]
+fn test_payout_owner_is_royalty_recipient() {
+    // the owner's royalty is ignored since they get the rest of the balance anyway
+    let royalty = HashMap::from([(accounts(1), 1_000), (accounts(2), 500)]);
+    let payout = payout_amounts(&royalty, &accounts(1), None, 1_000, 2);
+    assert_eq!(payout, HashMap::from([(accounts(1), 950), (accounts(2), 50)]));
+}
+
+#[test]
+fn test_payout_rounding_dust_goes_to_owner() {
+    let royalty = HashMap::from([(accounts(2), 3_333), (accounts(3), 3_333)]);
+    let payout = payout_amounts(&royalty, &accounts(1), None, 101, 3);
+    assert_eq!(payout, HashMap::from([(accounts(1), 35), (accounts(2), 33), (accounts(3), 33)]));
+    assert_eq!(payout.values().sum::<u128>(), 101);
+}
+
+#[test]
+fn test_payout_zero_balance() {
+    let royalty = HashMap::from([(accounts(2), 1_000)]);
+    let payout = payout_amounts(&royalty, &accounts(1), None, 0, 2);
+    assert_eq!(payout, HashMap::from([(accounts(1), 0), (accounts(2), 0)]));
+}
+
+#[test]
+fn test_payout_full_royalty() {
+    let royalty = HashMap::from([(accounts(2), 10_000)]);
+    let payout = payout_amounts(&royalty, &accounts(1), None, 1_000, 2);
+    assert_eq!(payout, HashMap::from([(accounts(1), 0), (accounts(2), 1_000)]));
+}
+
+#[test]
+#[should_panic(expected = "Market cannot payout to that many receivers")]
+fn test_payout_max_len_counts_owner() {
+    let royalty = HashMap::from([(accounts(2), 1_000), (accounts(3), 500)]);
+    payout_amounts(&royalty, &accounts(1), None, 1_000, 2);
+}
+
+#[test]
+#[should_panic(expected = "Market cannot payout to that many receivers")]
+fn test_payout_max_len_counts_platform_fee() {
+    let royalty = HashMap::from([(accounts(2), 1_000)]);
+    let platform_fee = PlatformFee { receiver_id: accounts(4), fee: 250 };
+    payout_amounts(&royalty, &accounts(1), Some(&platform_fee), 1_000, 2);
+}
+
+#[test]
+fn test_payout_with_platform_fee() {
+    let royalty = HashMap::from([(accounts(2), 1_000)]);
+    let platform_fee = PlatformFee { receiver_id: accounts(4), fee: 250 };
+    let payout = payout_amounts(&royalty, &accounts(1), Some(&platform_fee), 1_000, 3);
+    assert_eq!(payout, HashMap::from([(accounts(1), 875), (accounts(2), 100), (accounts(4), 25)]));
+}
+
+#[test]
+fn test_payout_platform_fee_receiver_is_royalty_recipient() {
+    let royalty = HashMap::from([(accounts(2), 1_000)]);
+    let platform_fee = PlatformFee { receiver_id: accounts(2), fee: 250 };
+    let payout = payout_amounts(&royalty, &accounts(1), Some(&platform_fee), 1_000, 2);
+    assert_eq!(payout, HashMap::from([(accounts(1), 875), (accounts(2), 125)]));
+}
+
+#[test]
+fn test_payout_platform_fee_receiver_is_owner() {
+    let royalty = HashMap::from([(accounts(2), 1_000)]);
+    let platform_fee = PlatformFee { receiver_id: accounts(1), fee: 250 };
+    let payout = payout_amounts(&royalty, &accounts(1), Some(&platform_fee), 1_000, 2);
+    assert_eq!(payout, HashMap::from([(accounts(1), 900), (accounts(2), 100)]));
+}
+
+#[test]
+fn test_payout_royalties_and_platform_fee_above_total() {
+    // the royalties are scaled down to the 9750 basis points the platform fee leaves
+    let royalty = HashMap::from([(accounts(2), 6_600), (accounts(3), 3_300)]);
+    let platform_fee = PlatformFee { receiver_id: accounts(4), fee: 250 };
+    let payout = payout_amounts(&royalty, &accounts(1), Some(&platform_fee), 1_000, 4);
+    assert_eq!(payout.get(&accounts(4)), Some(&25));
+    assert_eq!(payout.get(&accounts(2)), Some(&650));
+    assert_eq!(payout.get(&accounts(3)), Some(&325));
+    assert_eq!(payout.get(&accounts(1)), Some(&0));
+}
+
+#[test]
+fn test_nft_payout_with_platform_fee() {
+    use crate::royalty::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), Some(HashMap::from([(accounts(2), 1_000)])), None);
+    contract.set_platform_fee(Some(PlatformFee { receiver_id: accounts(4), fee: 250 }), None);
+
+    let payout = contract.nft_payout("0".to_string(), U128(1_000), 3).payout;
+    assert_eq!(payout.get(&accounts(1)), Some(&U128(875)));
+    assert_eq!(payout.get(&accounts(2)), Some(&U128(100)));
+    assert_eq!(payout.get(&accounts(4)), Some(&U128(25)));
+    assert_eq!(
+        contract.nft_royalty_split("0".to_string()),
+        HashMap::from([(accounts(1), 8_750), (accounts(2), 1_000), (accounts(4), 250)])
+    );
+}
+
+const APPROVAL_EXPIRES_AT: u64 = 1_672_531_200_000;
+
+// mint a token to alice and approve bob until the approval expiry
+fn setup_expiring_approval(context: &mut VMContextBuilder) -> Contract {
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(0), None, None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(CLAIM_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_approve("0".to_string(), accounts(1), None, Some(APPROVAL_EXPIRES_AT));
+    contract
+}
+
+#[test]
+fn test_nft_approve_with_expiry() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_expiring_approval(&mut context);
+    assert!(contract.nft_is_approved("0".to_string(), accounts(1), None));
+    assert_eq!(contract.nft_approval_expires_at("0".to_string(), accounts(1)), Some(APPROVAL_EXPIRES_AT));
+    assert!(near_sdk::test_utils::get_logs().iter().any(|log| log.contains(r#""event":"nft_approve""#)));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(0)
+        .block_timestamp(APPROVAL_EXPIRES_AT * 1_000_000)
+        .build());
+    assert!(!contract.nft_is_approved("0".to_string(), accounts(1), None));
+
+    // the expired approval is removed by pruning
+    assert_eq!(contract.nft_prune_expired_approvals("0".to_string()), 1);
+    assert_eq!(contract.nft_token("0".to_string()).unwrap().approved_account_ids, HashMap::new());
+    assert_eq!(contract.nft_approval_expires_at("0".to_string(), accounts(1)), None);
+    assert_eq!(contract.nft_prune_expired_approvals("0".to_string()), 0);
+}
+
+#[test]
+#[should_panic(expected = "Approval has expired")]
+fn test_transfer_with_expired_approval() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_expiring_approval(&mut context);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .block_timestamp(APPROVAL_EXPIRES_AT * 1_000_000)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_transfer(accounts(2), "0".to_string(), Some(0), None);
+}
+
+#[test]
+fn test_transfer_removes_approval_expiries() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_expiring_approval(&mut context);
+    assert_eq!(contract.approval_expires_at.get(&("0".to_string(), 0)), Some(APPROVAL_EXPIRES_AT));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_transfer(accounts(2), "0".to_string(), Some(0), None);
+    assert_eq!(contract.nft_token("0".to_string()).unwrap().owner_id, accounts(2));
+    assert_eq!(contract.approval_expires_at.get(&("0".to_string(), 0)), None);
+}
+
+#[test]
+#[should_panic(expected = "Approval expiry must be in the future")]
+fn test_nft_approve_expired() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.block_timestamp(APPROVAL_EXPIRES_AT * 1_000_000).build());
+    setup_expiring_approval(&mut context);
+}
+
+#[test]
+fn test_nft_revoke_all_logs_revoked_accounts() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_expiring_approval(&mut context);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_revoke_all("0".to_string());
+    assert_eq!(contract.nft_approval_expires_at("0".to_string(), accounts(1)), None);
+
+    let logs = near_sdk::test_utils::get_logs();
+    assert!(logs[0].contains(r#""event":"nft_revoke""#));
+    assert!(logs[0].contains(r#""account_ids":["bob"]"#));
+}
+
+// mint a token to bob and add charlie as compliance officer
+fn setup_compliance(context: &mut VMContextBuilder) -> Contract {
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, None);
+    contract.add_compliance_officer(accounts(2), None);
+    contract
+}
+
+#[test]
+fn test_force_transfer() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_compliance(&mut context);
+    assert!(contract.is_compliance_officer(accounts(2)));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(0)
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.freeze_account(accounts(1), None);
+    contract.nft_force_transfer("0".to_string(), accounts(3), "LOST_KEYS".to_string(), None);
+    assert_eq!(contract.nft_token("0".to_string()).unwrap().owner_id, accounts(3));
+
+    let logs = near_sdk::test_utils::get_logs();
+    assert!(logs.iter().any(|log| log.contains(r#""event":"nft_transfer""#)));
+    assert!(logs.iter().any(|log| log.contains(r#""event":"force_transfer""#) && log.contains("LOST_KEYS")));
+}
+
+#[test]
+#[should_panic(expected = "Reason code is required")]
+fn test_force_transfer_without_reason() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_compliance(&mut context);
+    contract.nft_force_transfer("0".to_string(), accounts(3), " ".to_string(), None);
+}
+
+#[test]
+#[should_panic(expected = "Caller not compliance officer")]
+fn test_force_transfer_not_compliance_officer() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_compliance(&mut context);
+
+    testing_env!(context
+        .predecessor_account_id(accounts(3))
+        .build());
+    contract.nft_force_transfer("0".to_string(), accounts(3), "LOST_KEYS".to_string(), None);
+}
+
+#[test]
+fn test_freeze_restricts_transfers() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_compliance(&mut context);
+
+    contract.freeze_token("0".to_string(), None);
+    assert!(contract.is_token_frozen("0".to_string()));
+    assert_eq!(contract.nft_transfer_restriction("0".to_string()), Some("Token is frozen".to_string()));
+    contract.unfreeze_token("0".to_string(), None);
+
+    contract.freeze_account(accounts(1), None);
+    assert_eq!(contract.nft_transfer_restriction("0".to_string()), Some("Account bob is frozen".to_string()));
+    contract.unfreeze_account(accounts(1), None);
+    assert_eq!(contract.nft_transfer_restriction("0".to_string()), None);
+}
+
+#[test]
+#[should_panic(expected = "Account charlie is frozen")]
+fn test_transfer_to_frozen_account() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_compliance(&mut context);
+    contract.freeze_account(accounts(2), None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_transfer(accounts(2), "0".to_string(), None, None);
+}
+
+#[test]
+fn test_snapshot_ownership() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, None);
+    contract.nft_mint("1".to_string(), sample_token_metadata(), accounts(1), None, None);
+    let first_snapshot = contract.take_snapshot(None);
+
+    // bob transfers a token to charlie after the first snapshot and a token is minted
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_transfer(accounts(2), "0".to_string(), None, None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("2".to_string(), sample_token_metadata(), accounts(2), None, None);
+    let second_snapshot = contract.take_snapshot(None);
+
+    // charlie transfers the token back after the second snapshot
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.nft_transfer(accounts(1), "0".to_string(), None, None);
+
+    assert_eq!(contract.owner_at(first_snapshot, "0".to_string()), Some(accounts(1)));
+    assert_eq!(contract.owner_at(second_snapshot, "0".to_string()), Some(accounts(2)));
+    assert_eq!(contract.owner_at(first_snapshot, "1".to_string()), Some(accounts(1)));
+    assert_eq!(contract.owner_at(first_snapshot, "2".to_string()), None);
+    assert_eq!(contract.owner_at(second_snapshot, "2".to_string()), Some(accounts(2)));
+
+    assert_eq!(contract.supply_for_owner_at(first_snapshot, accounts(1)), U128(2));
+    assert_eq!(contract.supply_for_owner_at(first_snapshot, accounts(2)), U128(0));
+    assert_eq!(contract.tokens_for_owner_at(second_snapshot, accounts(1), None, None), vec!["1".to_string()]);
+    let mut charlie_tokens = contract.tokens_for_owner_at(second_snapshot, accounts(2), None, None);
+    charlie_tokens.sort();
+    assert_eq!(charlie_tokens, vec!["0".to_string(), "2".to_string()]);
+}
+
+#[test]
+fn test_snapshot_checkpoints_are_pruned() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, None);
+
+    // the token changes owner after every snapshot
+    for i in 0..MAX_RETAINED_SNAPSHOTS + 5 {
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(0)
+            .predecessor_account_id(accounts(0))
+            .build());
+        contract.take_snapshot(None);
+
+        let (sender_id, receiver_id) = if i % 2 == 0 { (accounts(1), accounts(2)) } else { (accounts(2), accounts(1)) };
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .predecessor_account_id(sender_id)
+            .build());
+        contract.nft_transfer(receiver_id, "0".to_string(), None, None);
+    }
+
+    // only the copies for the snapshots that can still be queried are kept
+    assert_eq!(contract.token_checkpoints.get(&"0".to_string()).unwrap().len() as u64, MAX_RETAINED_SNAPSHOTS);
+    assert!(contract.owner_checkpoints.get(&accounts(1)).unwrap().len() as u64 <= MAX_RETAINED_SNAPSHOTS);
+    let latest_snapshot = MAX_RETAINED_SNAPSHOTS + 4;
+    assert_eq!(contract.owner_at(latest_snapshot, "0".to_string()), Some(accounts(1)));
+    assert_eq!(contract.owner_at(latest_snapshot - MAX_RETAINED_SNAPSHOTS + 1, "0".to_string()), Some(accounts(2)));
+}
+
+#[test]
+#[should_panic(expected = "Snapshot is no longer retained")]
+fn test_owner_at_pruned_snapshot() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context.predecessor_account_id(accounts(0)).build());
+    for _ in 0..MAX_RETAINED_SNAPSHOTS + 1 {
+        contract.take_snapshot(None);
+    }
+    contract.owner_at(0, "0".to_string());
+}
+
+#[test]
+fn test_burn_clears_snapshot_checkpoints() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, None);
+    contract.nft_mint("1".to_string(), sample_token_metadata(), accounts(1), None, None);
+    let snapshot_id = contract.take_snapshot(None);
+
+    // the token moves to charlie after the snapshot and is burned there
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_transfer(accounts(2), "0".to_string(), None, None);
+    contract.internal_burn(&"0".to_string(), None, None);
+
+    // nothing is left of the burned token in the snapshot
+    assert!(contract.token_checkpoints.get(&"0".to_string()).is_none());
+    assert_eq!(contract.owner_at(snapshot_id, "0".to_string()), None);
+    assert_eq!(contract.tokens_for_owner_at(snapshot_id, accounts(1), None, None), vec!["1".to_string()]);
+    assert!(contract.tokens_for_owner_at(snapshot_id, accounts(2), None, None).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "No snapshot")]
+fn test_owner_at_unknown_snapshot() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let contract = Contract::new_default_meta(accounts(0).into());
+    contract.owner_at(0, "0".to_string());
+}
+
+#[test]
+fn test_nft_token_history() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_compliance(&mut context);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(1))
+        .block_timestamp(1_000_000_000)
+        .build());
+    contract.nft_transfer(accounts(3), "0".to_string(), None, Some("gift".to_string()));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(0)
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.nft_force_transfer("0".to_string(), accounts(4), "COURT-ORDER".to_string(), None);
+
+    let history = contract.nft_token_history("0".to_string(), None, None);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].from_id, accounts(1));
+    assert_eq!(history[0].to_id, accounts(3));
+    assert_eq!(history[0].timestamp, 1_000);
+    assert_eq!(history[0].memo, Some("gift".to_string()));
+    assert_eq!(history[1].from_id, accounts(3));
+    assert_eq!(history[1].to_id, accounts(4));
+    assert_eq!(history[1].memo, Some("COURT-ORDER".to_string()));
+
+    let page = contract.nft_token_history("0".to_string(), Some(U128(1)), Some(1));
+    assert_eq!(page, vec![history[1].clone()]);
+    assert!(contract.nft_token_history("1".to_string(), None, None).is_empty());
+}
+
+#[test]
+fn test_nft_token_history_cap() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_compliance(&mut context);
+    assert_eq!(contract.history_cap(), DEFAULT_HISTORY_CAP);
+    contract.set_history_cap(2, None);
+
+    // the token goes back and forth between bob and charlie
+    for i in 0..4 {
+        let (sender_id, receiver_id) = if i % 2 == 0 { (accounts(1), accounts(3)) } else { (accounts(3), accounts(1)) };
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .predecessor_account_id(sender_id)
+            .build());
+        contract.nft_transfer(receiver_id, "0".to_string(), None, Some(i.to_string()));
+    }
+
+    // only the two most recent transfers are kept
+    let history = contract.nft_token_history("0".to_string(), None, None);
+    assert_eq!(contract.nft_token_history_length("0".to_string()), 2);
+    assert_eq!(history.iter().map(|record| record.memo.clone().unwrap()).collect::<Vec<String>>(), vec!["2", "3"]);
+}
+
+#[test]
+fn test_nft_token_history_long_memo() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_compliance(&mut context);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_transfer(accounts(3), "0".to_string(), None, Some("é".repeat(100)));
+
+    // only the first 64 bytes of the memo are kept, without splitting a character
+    let history = contract.nft_token_history("0".to_string(), None, None);
+    assert_eq!(history[0].memo, Some("é".repeat(32)));
+}
+
+#[test]
+fn test_burn_clears_token_history() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_compliance(&mut context);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.nft_transfer(accounts(3), "0".to_string(), None, None);
+    assert_eq!(contract.nft_token_history_length("0".to_string()), 1);
+
+    contract.internal_burn(&"0".to_string(), None, None);
+    assert_eq!(contract.nft_token_history_length("0".to_string()), 0);
+    assert_eq!(contract.token_history.get(&("0".to_string(), 0)), None);
+}
+
+#[test]
+#[should_panic(expected = "Caller not owner")]
+fn test_set_history_cap_not_owner() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_compliance(&mut context);
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.set_history_cap(2, None);
+}
+
+#[test]
+fn test_mint_from_storage_balance() {
+    use near_contract_standards::storage_management::StorageManagement;
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+    assert!(contract.storage_balance_of(accounts(0)).is_none());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let balance = contract.storage_deposit(None, None);
+    let min_balance = contract.storage_balance_bounds().min.0;
+    assert_eq!(balance.total.0, MINT_STORAGE_COST);
+    assert_eq!(balance.available.0, MINT_STORAGE_COST - min_balance);
+
+    // minting and approving without a storage deposit draw from the storage balance
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(0)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(0), None, None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_approve("0".to_string(), accounts(1), None, None);
+
+    let balance = contract.storage_balance_of(accounts(0)).unwrap();
+    assert!(balance.total.0 < MINT_STORAGE_COST);
+    assert_eq!(balance.total.0 - balance.available.0, min_balance);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let balance = contract.storage_withdraw(None);
+    assert_eq!(balance.total.0, min_balance);
+    assert_eq!(balance.available.0, 0);
+
+    assert!(contract.storage_unregister(None));
+    assert!(contract.storage_balance_of(accounts(0)).is_none());
+    assert!(!contract.storage_unregister(None));
+}
+
+#[test]
+#[should_panic(expected = "Must attach")]
+fn test_mint_with_insufficient_storage_balance() {
+    use near_contract_standards::storage_management::StorageManagement;
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    // only register the account so the storage balance can't cover the mint
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let balance = contract.storage_deposit(None, Some(true));
+    assert_eq!(balance.available.0, 0);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(0)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(0), None, None);
+}
+
+#[test]
+#[should_panic(expected = "Amount exceeds the available storage balance")]
+fn test_storage_withdraw_too_much() {
+    use near_contract_standards::storage_management::StorageManagement;
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.storage_deposit(None, None);
+
+    testing_env!(context
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.storage_withdraw(Some(U128(MINT_STORAGE_COST)));
+}
+
+#[test]
+fn test_migrate() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+    assert_eq!(contract.schema_version(), SCHEMA_VERSION);
+    contract.set_history_cap(2, None);
+    env::state_write(&contract);
+
+    let contract = Contract::migrate();
+    assert_eq!(contract.schema_version(), SCHEMA_VERSION);
+    assert_eq!(contract.history_cap(), 2);
+}
+
+#[test]
+fn test_migrate_from_schema_version_1() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+    contract.set_history_cap(2, None);
+    contract.set_soulbound(true, None);
+    contract.set_day_count_convention(DayCountConvention::Actual360, None);
+
+    // the state of version 1 is the current state without the soulbound and day count convention fields at the end
+    let mut state = contract.try_to_vec().unwrap();
+    state.truncate(state.len() - 2);
+    env::storage_write(b"STATE", &state);
+    write_schema_version(1);
+
+    let contract = Contract::migrate();
+    assert_eq!(contract.schema_version(), SCHEMA_VERSION);
+    assert_eq!(contract.history_cap(), 2);
+    assert!(!contract.soulbound);
+    assert_eq!(contract.day_count_convention(), DayCountConvention::Thirty360);
+}
+
+#[test]
+fn test_migrate_from_schema_version_2() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+    contract.set_soulbound(true, None);
+    contract.set_day_count_convention(DayCountConvention::Actual360, None);
+
+    // the state of version 2 is the current state without the day count convention field at the end
+    let mut state = contract.try_to_vec().unwrap();
+    state.truncate(state.len() - 1);
+    env::storage_write(b"STATE", &state);
+    write_schema_version(2);
+
+    let contract = Contract::migrate();
+    assert_eq!(contract.schema_version(), SCHEMA_VERSION);
+    assert!(contract.soulbound);
+    assert_eq!(contract.day_count_convention(), DayCountConvention::Thirty360);
+}
+
+#[test]
+#[should_panic(expected = "Cannot migrate from schema version 0")]
+fn test_migrate_unknown_schema_version() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    Contract::migrate();
+}
+
+#[test]
+#[should_panic(expected = "Caller not owner")]
+fn test_upgrade_not_owner() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.upgrade(vec![0u8; 8].into());
+}
+
+#[test]
+#[should_panic(expected = "Not enough gas attached to upgrade")]
+fn test_upgrade_not_enough_gas() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .prepaid_gas(Gas(5_000_000_000_000))
+        .build());
+    contract.upgrade(vec![0u8; 8].into());
+}
+
+#[test]
+fn test_soulbound_redeem() {
+    use crate::NonFungibleTokenMetadata;
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+    assert!(!contract.nft_metadata().soulbound);
+    contract.set_soulbound(true, None);
+    assert!(contract.nft_metadata().soulbound);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let token_id = "0".to_string();
+    contract.nft_mint(token_id.clone(), sample_token_metadata(), accounts(1), None, Some(sample_bond_terms()));
+    assert_eq!(
+        contract.nft_transfer_restriction(token_id.clone()),
+        Some("Bonds of this contract are non-transferable".to_string())
+    );
+
+    let face_value = sample_bond_terms().face_value.0;
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(face_value)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.principal_deposit(None);
+
+    // the issuer redeems the bond on behalf of bob after maturity
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .block_timestamp(sample_bond_terms().maturity_date * 1_000_000)
+        .predecessor_account_id(accounts(0))
+        .build());
+    let amount = contract.nft_redeem(token_id, None, None);
+    assert_eq!(amount, U128(face_value));
+    assert_eq!(contract.nft_tokens_for_owner(accounts(1), None, None).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Bonds of this contract are non-transferable")]
+fn test_soulbound_transfer() {
+    use crate::nft_core::NonFungibleTokenCore;
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+    contract.set_soulbound(true, None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(0), None, None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(1)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_transfer(accounts(1), "0".to_string(), None, None);
+}
+
+#[test]
+#[should_panic(expected = "Bonds of this contract are non-transferable")]
+fn test_soulbound_approve() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+    contract.set_soulbound(true, None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(0), None, None);
+    contract.nft_approve("0".to_string(), accounts(1), None, None);
+}
+
+#[test]
+#[should_panic(expected = "Cannot change the transfer mode after the first mint")]
+fn test_set_soulbound_after_mint() {
+    let mut context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(0), None, None);
+    contract.set_soulbound(true, None);
+}
+
+fn setup_cashflow(context: &mut VMContextBuilder, day_count_convention: DayCountConvention) -> Contract {
+    testing_env!(context.build());
+    let mut contract = Contract::new_default_meta(accounts(0).into());
+    contract.set_day_count_convention(day_count_convention, None);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(MINT_STORAGE_COST)
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.nft_mint("0".to_string(), sample_token_metadata(), accounts(1), None, Some(sample_bond_terms()));
+    contract
+}
+
+#[test]
+fn test_coupon_schedule() {
+    let mut context = get_context(accounts(0));
+    let contract = setup_cashflow(&mut context, DayCountConvention::Thirty360);
+    let face_value = sample_bond_terms().face_value.0;
+
+    // semi-annual coupons from 2023-01-01 until maturity on 2026-01-01
+    let schedule = contract.nft_coupon_schedule("0".to_string(), None, None);
+    assert_eq!(schedule.len(), 6);
+    assert_eq!(schedule[0].period_start, sample_bond_terms().issue_date);
+    assert_eq!(schedule[0].payment_date, 1_688_169_600_000);
+    assert_eq!(schedule[1].period_start, 1_688_169_600_000);
+    assert_eq!(schedule[5].payment_date, sample_bond_terms().maturity_date);
+    for payment in schedule.iter() {
+        assert_eq!(payment.coupon, U128(face_value / 40));
+    }
+    assert_eq!(schedule[4].principal, U128(0));
+    assert_eq!(schedule[5].principal, U128(face_value));
+
+    let page = contract.nft_coupon_schedule("0".to_string(), Some(U128(5)), Some(10));
+    assert_eq!(page, vec![schedule[5].clone()]);
+}
+
+#[test]
+fn test_accrued_interest() {
+    let mut context = get_context(accounts(0));
+    let contract = setup_cashflow(&mut context, DayCountConvention::Thirty360);
+    let face_value = sample_bond_terms().face_value.0;
+
+    // three months into the first half year
+    assert_eq!(contract.nft_accrued_interest("0".to_string(), Some(1_680_307_200_000)), U128(face_value / 80));
+    assert_eq!(contract.nft_next_payment_date("0".to_string(), Some(1_680_307_200_000)), Some(1_688_169_600_000));
+
+    // nothing has accrued on a payment date, before issue or after maturity
+    assert_eq!(contract.nft_accrued_interest("0".to_string(), Some(1_688_169_600_000)), U128(0));
+    assert_eq!(contract.nft_accrued_interest("0".to_string(), Some(0)), U128(0));
+    assert_eq!(contract.nft_accrued_interest("0".to_string(), Some(sample_bond_terms().maturity_date)), U128(0));
+    assert_eq!(contract.nft_next_payment_date("0".to_string(), Some(sample_bond_terms().maturity_date)), None);
+}
+
+#[test]
+fn test_coupon_schedule_actual_365() {
+    let mut context = get_context(accounts(0));
+    let contract = setup_cashflow(&mut context, DayCountConvention::Actual365Fixed);
+    assert_eq!(contract.day_count_convention(), DayCountConvention::Actual365Fixed);
+
+    // the first half year has 181 days
+    let schedule = contract.nft_coupon_schedule("0".to_string(), None, Some(1));
+    assert_eq!(schedule[0].coupon, U128(24_794_520_547_945_205_479_452));
+}
+
+#[test]
+fn test_payment_dates_end_of_month() {
+    // monthly coupons from 2024-01-31 until 2025-01-15
+    let bond_terms = BondTerms {
+        coupon_frequency: 12,
+        issue_date: 1_706_659_200_000,
+        maturity_date: 1_736_899_200_000,
+        ..sample_bond_terms()
+    };
+    assert_eq!(bond_terms.payment_count(), 12);
+    assert_eq!(bond_terms.payment_date(1), 1_709_164_800_000);
+    assert_eq!(bond_terms.payment_date(2), 1_711_843_200_000);
+    assert_eq!(bond_terms.payment_date(12), bond_terms.maturity_date);
+
+    // the period of a timestamp is found without going through the dates before it
+    let day = 86_400_000;
+    for timestamp in (bond_terms.issue_date..bond_terms.maturity_date).step_by(day as usize / 2) {
+        let payments = bond_terms.payments_until(timestamp);
+        assert!(bond_terms.payment_date(payments) <= timestamp);
+        assert!(bond_terms.payment_date(payments + 1) > timestamp);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Coupon amount overflows, the face value is too large")]
+fn test_accrued_interest_overflow() {
+    let bond_terms = BondTerms {
+        face_value: U128(u128::MAX / 100),
+        ..sample_bond_terms()
+    };
+    bond_terms.coupon_between(bond_terms.issue_date, bond_terms.maturity_date, DayCountConvention::Actual365Fixed);
+}
+
+#[test]
+fn test_add_months_end_of_month() {
+    // 2024-01-31 plus one month is the last day of February in a leap year
+    assert_eq!(add_months(1_706_659_200_000, 1), 1_709_164_800_000);
+    assert_eq!(add_months(1_704_067_200_000, 12), 1_735_689_600_000);
+}
+
+#[test]
+#[should_panic(expected = "Cannot change the day count convention after the first mint")]
+fn test_set_day_count_convention_after_mint() {
+    let mut context = get_context(accounts(0));
+    let mut contract = setup_cashflow(&mut context, DayCountConvention::Thirty360);
+    contract.set_day_count_convention(DayCountConvention::Actual360, None);
+}
@@ -0,0 +1,402 @@
+use near_sdk::Gas;
+
+use crate::*;
+
+/// The version of the layout of the contract state. Bump it and add a migration whenever the layout changes
+pub const SCHEMA_VERSION: u32 = 3;
+
+/*
+    The schema version is stored under its own key instead of in the contract struct.
+    That way it can be read before the state is deserialized, which is needed to know what layout to deserialize.
+*/
+const SCHEMA_VERSION_KEY: &[u8] = b"SCHEMA_VERSION";
+
+const GAS_FOR_UPGRADE: Gas = Gas(10_000_000_000_000);
+
+/*
+    The layout of the contract state at schema version 1, before the transfer mode was stored.
+    Only used to read the state of contracts that were deployed at that version.
+*/
+#[derive(BorshDeserialize)]
+pub(crate) struct ContractV1 {
+    // contract owner
+    owner_id: AccountId,
+
+    // keeps track of all the token IDs for given account
+    tokens_per_owner: LookupMap<AccountId, UnorderedSet<TokenId>>,
+
+    // keeps track of token struct for given token ID
+    tokens_by_id: LookupMap<TokenId, Token>,
+
+    // keeps track of the token metadata for a given token ID
+    token_metadata_by_id: UnorderedMap<TokenId, TokenMetadata>,
+
+    // keeps track of the bond terms for a given token ID
+    bond_terms_by_id: LookupMap<TokenId, BondTerms>,
+
+    // keeps track of the metadata for the contract
+    metadata: LazyOption<NFTContractMetadata>,
+
+    // keeps track of the accounts that are allowed to mint tokens besides the owner
+    minters: UnorderedSet<AccountId>,
+
+    // keeps track of the order in which tokens were minted. Used to decide which tokens are eligible for a coupon
+    mint_index_by_id: LookupMap<TokenId, u64>,
+
+    // the mint index that will be given to the next minted token
+    next_mint_index: u64,
+
+    // keeps track of all the coupon periods that were funded by the owner
+    coupon_periods: Vector<CouponPeriod>,
+
+    // keeps track of the (period ID, token ID) pairs for which the coupon has been claimed
+    coupon_claims: LookupSet<(u64, TokenId)>,
+
+    // the amount of yoctoNEAR deposited by the owner to pay out the principal of matured bonds
+    principal_balance: Balance,
+
+    // keeps track of the bond series for a given series ID
+    series_by_id: UnorderedMap<String, BondSeries>,
+
+    // keeps track of all the token IDs for a given series
+    tokens_per_series: LookupMap<String, UnorderedSet<TokenId>>,
+
+    // keeps track of the series for a given token ID if it was minted into one
+    series_by_token_id: LookupMap<TokenId, String>,
+
+    // keeps track of the series for which transfers are locked
+    series_locked: UnorderedSet<String>,
+
+    // keeps track of the time based transfer lockups for a given lockup ID
+    transfer_lockups: UnorderedMap<u64, TransferLockup>,
+
+    // the lockup ID that will be given to the next transfer lockup
+    next_lockup_id: u64,
+
+    // the user registry that receivers of transfers are checked against, if any
+    transfer_registry: Option<TransferRegistry>,
+
+    // keeps track of the primary sale for a given series ID
+    sales: UnorderedMap<String, Sale>,
+
+    // keeps track of the number of bonds a buyer has bought in the sale of a series
+    purchases_per_buyer: LookupMap<(String, AccountId), u64>,
+
+    // keeps track of what an investor contributed to the campaign of a series while the proceeds are in escrow
+    contributions: LookupMap<(String, AccountId), Contribution>,
+
+    // the perpetual royalties given to newly minted tokens when none are passed in
+    default_royalty: HashMap<AccountId, u32>,
+
+    // the fee the platform receives on every sale that uses a payout, if any
+    platform_fee: Option<PlatformFee>,
+
+    // keeps track of when the approvals that expire do so for a given (token ID, approval ID)
+    approval_expires_at: LookupMap<(TokenId, u64), u64>,
+
+    // keeps track of the accounts that are allowed to force transfers and freeze tokens and accounts besides the owner
+    compliance_officers: UnorderedSet<AccountId>,
+
+    // keeps track of the accounts that can't move tokens until they are unfrozen
+    frozen_accounts: UnorderedSet<AccountId>,
+
+    // keeps track of the tokens that can't be moved until they are unfrozen
+    frozen_tokens: UnorderedSet<TokenId>,
+
+    // keeps track of all the snapshots that were taken by the owner
+    snapshots: Vector<Snapshot>,
+
+    // keeps track of the owner of a token at the snapshots it changed after
+    token_checkpoints: LookupMap<TokenId, Vec<TokenCheckpoint>>,
+
+    // keeps track of the tokens of an account at the snapshots they changed after
+    owner_checkpoints: LookupMap<AccountId, Vec<OwnerCheckpoint>>,
+
+    // keeps track of the ownership history of a given (token ID, index)
+    token_history: LookupMap<(TokenId, u64), TransferRecord>,
+
+    // keeps track of the index of the oldest and the next history entry for a given token ID
+    token_history_bounds: LookupMap<TokenId, (u64, u64)>,
+
+    // the maximum number of history entries kept per token
+    history_cap: u64,
+
+    // keeps track of the prepaid storage balance of a given account ID
+    storage_deposits: LookupMap<AccountId, Balance>,
+}
+
+impl From<ContractV1> for ContractV2 {
+    fn from(old: ContractV1) -> Self {
+        Self {
+            owner_id: old.owner_id,
+            tokens_per_owner: old.tokens_per_owner,
+            tokens_by_id: old.tokens_by_id,
+            token_metadata_by_id: old.token_metadata_by_id,
+            bond_terms_by_id: old.bond_terms_by_id,
+            metadata: old.metadata,
+            minters: old.minters,
+            mint_index_by_id: old.mint_index_by_id,
+            next_mint_index: old.next_mint_index,
+            coupon_periods: old.coupon_periods,
+            coupon_claims: old.coupon_claims,
+            principal_balance: old.principal_balance,
+            series_by_id: old.series_by_id,
+            tokens_per_series: old.tokens_per_series,
+            series_by_token_id: old.series_by_token_id,
+            series_locked: old.series_locked,
+            transfer_lockups: old.transfer_lockups,
+            next_lockup_id: old.next_lockup_id,
+            transfer_registry: old.transfer_registry,
+            sales: old.sales,
+            purchases_per_buyer: old.purchases_per_buyer,
+            contributions: old.contributions,
+            default_royalty: old.default_royalty,
+            platform_fee: old.platform_fee,
+            approval_expires_at: old.approval_expires_at,
+            compliance_officers: old.compliance_officers,
+            frozen_accounts: old.frozen_accounts,
+            frozen_tokens: old.frozen_tokens,
+            snapshots: old.snapshots,
+            token_checkpoints: old.token_checkpoints,
+            owner_checkpoints: old.owner_checkpoints,
+            token_history: old.token_history,
+            token_history_bounds: old.token_history_bounds,
+            history_cap: old.history_cap,
+            storage_deposits: old.storage_deposits,
+            // the bonds of contracts that were deployed before the transfer mode existed are transferable
+            soulbound: false,
+        }
+    }
+}
+
+/*
+    The layout of the contract state at schema version 2, before the day count convention was stored.
+    Only used to read the state of contracts that were deployed at that version.
+*/
+#[derive(BorshDeserialize)]
+pub(crate) struct ContractV2 {
+    // contract owner
+    owner_id: AccountId,
+
+    // keeps track of all the token IDs for given account
+    tokens_per_owner: LookupMap<AccountId, UnorderedSet<TokenId>>,
+
+    // keeps track of token struct for given token ID
+    tokens_by_id: LookupMap<TokenId, Token>,
+
+    // keeps track of the token metadata for a given token ID
+    token_metadata_by_id: UnorderedMap<TokenId, TokenMetadata>,
+
+    // keeps track of the bond terms for a given token ID
+    bond_terms_by_id: LookupMap<TokenId, BondTerms>,
+
+    // keeps track of the metadata for the contract
+    metadata: LazyOption<NFTContractMetadata>,
+
+    // keeps track of the accounts that are allowed to mint tokens besides the owner
+    minters: UnorderedSet<AccountId>,
+
+    // keeps track of the order in which tokens were minted. Used to decide which tokens are eligible for a coupon
+    mint_index_by_id: LookupMap<TokenId, u64>,
+
+    // the mint index that will be given to the next minted token
+    next_mint_index: u64,
+
+    // keeps track of all the coupon periods that were funded by the owner
+    coupon_periods: Vector<CouponPeriod>,
+
+    // keeps track of the (period ID, token ID) pairs for which the coupon has been claimed
+    coupon_claims: LookupSet<(u64, TokenId)>,
+
+    // the amount of yoctoNEAR deposited by the owner to pay out the principal of matured bonds
+    principal_balance: Balance,
+
+    // keeps track of the bond series for a given series ID
+    series_by_id: UnorderedMap<String, BondSeries>,
+
+    // keeps track of all the token IDs for a given series
+    tokens_per_series: LookupMap<String, UnorderedSet<TokenId>>,
+
+    // keeps track of the series for a given token ID if it was minted into one
+    series_by_token_id: LookupMap<TokenId, String>,
+
+    // keeps track of the series for which transfers are locked
+    series_locked: UnorderedSet<String>,
+
+    // keeps track of the time based transfer lockups for a given lockup ID
+    transfer_lockups: UnorderedMap<u64, TransferLockup>,
+
+    // the lockup ID that will be given to the next transfer lockup
+    next_lockup_id: u64,
+
+    // the user registry that receivers of transfers are checked against, if any
+    transfer_registry: Option<TransferRegistry>,
+
+    // keeps track of the primary sale for a given series ID
+    sales: UnorderedMap<String, Sale>,
+
+    // keeps track of the number of bonds a buyer has bought in the sale of a series
+    purchases_per_buyer: LookupMap<(String, AccountId), u64>,
+
+    // keeps track of what an investor contributed to the campaign of a series while the proceeds are in escrow
+    contributions: LookupMap<(String, AccountId), Contribution>,
+
+    // the perpetual royalties given to newly minted tokens when none are passed in
+    default_royalty: HashMap<AccountId, u32>,
+
+    // the fee the platform receives on every sale that uses a payout, if any
+    platform_fee: Option<PlatformFee>,
+
+    // keeps track of when the approvals that expire do so for a given (token ID, approval ID)
+    approval_expires_at: LookupMap<(TokenId, u64), u64>,
+
+    // keeps track of the accounts that are allowed to force transfers and freeze tokens and accounts besides the owner
+    compliance_officers: UnorderedSet<AccountId>,
+
+    // keeps track of the accounts that can't move tokens until they are unfrozen
+    frozen_accounts: UnorderedSet<AccountId>,
+
+    // keeps track of the tokens that can't be moved until they are unfrozen
+    frozen_tokens: UnorderedSet<TokenId>,
+
+    // keeps track of all the snapshots that were taken by the owner
+    snapshots: Vector<Snapshot>,
+
+    // keeps track of the owner of a token at the snapshots it changed after
+    token_checkpoints: LookupMap<TokenId, Vec<TokenCheckpoint>>,
+
+    // keeps track of the tokens of an account at the snapshots they changed after
+    owner_checkpoints: LookupMap<AccountId, Vec<OwnerCheckpoint>>,
+
+    // keeps track of the ownership history of a given (token ID, index)
+    token_history: LookupMap<(TokenId, u64), TransferRecord>,
+
+    // keeps track of the index of the oldest and the next history entry for a given token ID
+    token_history_bounds: LookupMap<TokenId, (u64, u64)>,
+
+    // the maximum number of history entries kept per token
+    history_cap: u64,
+
+    // keeps track of the prepaid storage balance of a given account ID
+    storage_deposits: LookupMap<AccountId, Balance>,
+
+    // whether the bonds of the contract can never be transferred
+    soulbound: bool,
+}
+
+impl From<ContractV2> for Contract {
+    fn from(old: ContractV2) -> Self {
+        Self {
+            owner_id: old.owner_id,
+            tokens_per_owner: old.tokens_per_owner,
+            tokens_by_id: old.tokens_by_id,
+            token_metadata_by_id: old.token_metadata_by_id,
+            bond_terms_by_id: old.bond_terms_by_id,
+            metadata: old.metadata,
+            minters: old.minters,
+            mint_index_by_id: old.mint_index_by_id,
+            next_mint_index: old.next_mint_index,
+            coupon_periods: old.coupon_periods,
+            coupon_claims: old.coupon_claims,
+            principal_balance: old.principal_balance,
+            series_by_id: old.series_by_id,
+            tokens_per_series: old.tokens_per_series,
+            series_by_token_id: old.series_by_token_id,
+            series_locked: old.series_locked,
+            transfer_lockups: old.transfer_lockups,
+            next_lockup_id: old.next_lockup_id,
+            transfer_registry: old.transfer_registry,
+            sales: old.sales,
+            purchases_per_buyer: old.purchases_per_buyer,
+            contributions: old.contributions,
+            default_royalty: old.default_royalty,
+            platform_fee: old.platform_fee,
+            approval_expires_at: old.approval_expires_at,
+            compliance_officers: old.compliance_officers,
+            frozen_accounts: old.frozen_accounts,
+            frozen_tokens: old.frozen_tokens,
+            snapshots: old.snapshots,
+            token_checkpoints: old.token_checkpoints,
+            owner_checkpoints: old.owner_checkpoints,
+            token_history: old.token_history,
+            token_history_bounds: old.token_history_bounds,
+            history_cap: old.history_cap,
+            storage_deposits: old.storage_deposits,
+            soulbound: old.soulbound,
+            // the coupons of contracts that were deployed before the convention could be chosen were counted with 30/360
+            day_count_convention: DayCountConvention::Thirty360,
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /*
+        Deploy new code on the contract and migrate the state afterwards - caller has to be contract owner.
+        All the GAS that is left is given to the migration.
+    */
+    pub fn upgrade(&mut self, code: Base64VecU8) -> Promise {
+        // make sure the caller is the owner
+        self.assert_owner();
+
+        // make sure there is gas left for the migration after the upgrade itself
+        let remaining_gas = env::prepaid_gas().0.saturating_sub(env::used_gas().0);
+        assert!(remaining_gas > GAS_FOR_UPGRADE.0, "Not enough gas attached to upgrade");
+        let gas_for_migrate = Gas(remaining_gas - GAS_FOR_UPGRADE.0);
+
+        Promise::new(env::current_account_id())
+            .deploy_contract(code.into())
+            .function_call("migrate".to_string(), vec![], 0, gas_for_migrate)
+    }
+
+    /*
+        Migrate the state that was written by an older version of the contract to the current layout.
+        Can only be called by the contract itself, as part of an upgrade.
+    */
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let from_version = read_schema_version();
+
+        let contract: Contract = match from_version {
+            SCHEMA_VERSION => env::state_read().expect("Contract is not initialized"),
+            // migrations from older layouts are added here, reading the old struct and converting it
+            1 => Contract::from(ContractV2::from(env::state_read::<ContractV1>().expect("Contract is not initialized"))),
+            2 => Contract::from(env::state_read::<ContractV2>().expect("Contract is not initialized")),
+            _ => env::panic_str(&format!("Cannot migrate from schema version {}", from_version)),
+        };
+        write_schema_version(SCHEMA_VERSION);
+
+        // construct the migrate log
+        let migrate_log: EventLog = EventLog {
+            standard: MICROBOND_STANDARD_NAME.to_string(),
+            version: MICROBOND_EVENT_VERSION.to_string(),
+            event: EventLogVariant::Migrate(vec![MigrateLog {
+                from_version,
+                to_version: SCHEMA_VERSION,
+            }]),
+        };
+
+        // log the serialized json
+        env::log_str(&migrate_log.to_string());
+
+        contract
+    }
+
+    //Query for the version of the layout of the contract state
+    pub fn schema_version(&self) -> u32 {
+        read_schema_version()
+    }
+}
+
+// read the version of the layout of the contract state. Contracts that were initialized without one have version 0
+pub(crate) fn read_schema_version() -> u32 {
+    env::storage_read(SCHEMA_VERSION_KEY)
+        .map(|bytes| u32::try_from_slice(&bytes).expect("Invalid schema version"))
+        .unwrap_or(0)
+}
+
+// store the version of the layout of the contract state
+pub(crate) fn write_schema_version(version: u32) {
+    env::storage_write(SCHEMA_VERSION_KEY, &version.try_to_vec().unwrap());
+}
@@ -10,7 +10,9 @@ use near_sdk::{serde::{Deserialize, Serialize}, serde_json};
 #[serde(crate = "near_sdk::serde")]
 #[non_exhaustive]
 pub enum EventLogVariant {
-    AddUser(Vec<AddUserLog>)
+    AddUser(Vec<AddUserLog>),
+    LinkAccount(Vec<LinkAccountLog>),
+    ChangeAccount(Vec<ChangeAccountLog>),
 }
 
 /// Interface to capture data about an event
@@ -50,4 +52,34 @@ pub struct AddUserLog {
     pub user_id: String,
     pub municipality_id: String,
     pub memo: Option<String>,
+}
+
+/// An event log to capture linking an account to a user
+/// 
+/// Arguments:
+/// * `user_id`: the user id of the user the account is linked to
+/// * `account_id`: the account id being linked
+/// * `memo` (optional): a memo to add to the event log
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LinkAccountLog {
+    pub user_id: String,
+    pub account_id: String,
+    pub memo: Option<String>,
+}
+
+/// An event log to capture changing the account linked to a user
+/// 
+/// Arguments:
+/// * `user_id`: the user id of the user the account is linked to
+/// * `old_account_id`: the account id that was linked before
+/// * `new_account_id`: the account id that is linked now
+/// * `memo` (optional): a memo to add to the event log
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChangeAccountLog {
+    pub user_id: String,
+    pub old_account_id: String,
+    pub new_account_id: String,
+    pub memo: Option<String>,
 }
\ No newline at end of file
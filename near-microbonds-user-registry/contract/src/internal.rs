@@ -1,5 +1,14 @@
 use crate::*;
 
+// Used to generate a unique prefix in the storage for the users of a municipality
+pub(crate) fn hash_municipality_id(municipality_id: &str) -> CryptoHash {
+    // Get the default hash
+    let mut hash = CryptoHash::default();
+    // We hash the municipality id and return it
+    hash.copy_from_slice(&env::sha256(municipality_id.as_bytes()));
+    hash
+}
+
 impl Contract {
     // Private function to assert if the called is the owner
     pub(crate) fn assert_owner(&self) {
@@ -1,4 +1,4 @@
-use near_sdk::{AccountId, collections::{LookupMap, UnorderedSet}, PanicOnDefault, near_bindgen, BorshStorageKey, env::{self}};
+use near_sdk::{AccountId, collections::{LookupMap, UnorderedSet}, PanicOnDefault, near_bindgen, BorshStorageKey, env::{self}, CryptoHash};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::json_types::{U128};
 use near_sdk::serde::{Deserialize, Serialize};
@@ -24,13 +24,23 @@ pub struct Contract {
     /// This tracks which users have been approved by which municipality
     /// MunicipalityId => [] of UserIds
     pub municipality_to_users: LookupMap<String, UnorderedSet<String>>,
+
+    /// This tracks which account is linked to which user
+    /// UserId => AccountId
+    pub user_to_account: LookupMap<String, AccountId>,
+
+    /// This tracks which user an account is linked to
+    /// AccountId => UserId
+    pub account_to_user: LookupMap<AccountId, String>,
 }
 
 #[derive(BorshStorageKey, BorshSerialize)]
 pub enum StorageKey {
     MunicipalityToUsers,
-    MunicipalityToUsersInner,
+    MunicipalityToUsersInner { municipality_hash: CryptoHash },
     MuncipalityToUsersVector,
+    UserToAccount,
+    AccountToUser,
 }
 
 #[near_bindgen]
@@ -47,7 +57,9 @@ impl Contract {
         let this = Self {
             // Set the owner_id field equal to the passed in owner_id
             owner_id,
-            municipality_to_users: LookupMap::new(StorageKey::MunicipalityToUsers.try_to_vec().unwrap())
+            municipality_to_users: LookupMap::new(StorageKey::MunicipalityToUsers.try_to_vec().unwrap()),
+            user_to_account: LookupMap::new(StorageKey::UserToAccount.try_to_vec().unwrap()),
+            account_to_user: LookupMap::new(StorageKey::AccountToUser.try_to_vec().unwrap())
         };
 
         // Return the Contract object
@@ -13,8 +13,14 @@ impl Contract {
 
         // Get the UnorderedSet of users for the given municipality
         let mut users = self.municipality_to_users.get(&municipality_id).unwrap_or_else(|| {
-            // If the UnorderedSet doesn't exist, create a new one
-            UnorderedSet::new(StorageKey::MunicipalityToUsersInner.try_to_vec().unwrap())
+            // If the UnorderedSet doesn't exist, create a new one with a unique prefix for the municipality
+            UnorderedSet::new(
+                StorageKey::MunicipalityToUsersInner {
+                    municipality_hash: hash_municipality_id(&municipality_id),
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
         });
 
         // Add the user to the UnorderedSet if it doesn't already exist
@@ -37,4 +43,65 @@ impl Contract {
             }]),
         }.to_string());
     }
+
+    /// Links an account to a user so contracts can check the account against the municipalities of the user
+    /// Arguments:
+    /// * `user_id`: the user id of the user the account belongs to
+    /// * `account_id`: the account id being linked
+    pub fn link_account_to_user(
+        &mut self, 
+        user_id: String, 
+        account_id: AccountId
+    ) {
+        // Assert that the caller is the owner
+        self.assert_owner();
+
+        // Assert that the account isn't linked to another user
+        if let Some(existing_user_id) = self.account_to_user.get(&account_id) {
+            assert_eq!(
+                existing_user_id,
+                user_id,
+                "Account is already linked to another user"
+            );
+        }
+
+        // Fetches the account id for the given user id
+        let account = self.user_to_account.get(&user_id);
+
+        // If the account is None, link the account and log the event with LinkAccountLog
+        // If the account is the same as the given account id, do nothing
+        // Otherwise unlink the old account, link the new one and log the event with ChangeAccountLog
+        match account {
+            None => {
+                self.user_to_account.insert(&user_id, &account_id);
+                self.account_to_user.insert(&account_id, &user_id);
+                env::log_str(&EventLog {
+                    version: "1.0.0".to_string(),
+                    event: EventLogVariant::LinkAccount(vec![LinkAccountLog {
+                        user_id,
+                        account_id: account_id.to_string(),
+                        memo: None,
+                    }]),
+                }.to_string());
+            },
+            Some(existing_account_id) => {
+                if existing_account_id == account_id {
+                    return;
+                }
+
+                self.account_to_user.remove(&existing_account_id);
+                self.user_to_account.insert(&user_id, &account_id);
+                self.account_to_user.insert(&account_id, &user_id);
+                env::log_str(&EventLog {
+                    version: "1.0.0".to_string(),
+                    event: EventLogVariant::ChangeAccount(vec![ChangeAccountLog {
+                        user_id,
+                        old_account_id: existing_account_id.to_string(),
+                        new_account_id: account_id.to_string(),
+                        memo: None,
+                    }]),
+                }.to_string());
+            }
+        }
+    }
 }
\ No newline at end of file
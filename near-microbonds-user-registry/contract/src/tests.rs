@@ -44,4 +44,54 @@ fn test_add_user_to_municipality_not_owner() {
     testing_env!(context.build());
     let mut contract = Contract::new(accounts(0));
     contract.add_user_to_municipality("municipality1".to_string(), "user1".to_string());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_link_account_to_user() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new(accounts(0));
+    contract.add_user_to_municipality("municipality1".to_string(), "user1".to_string());
+    contract.link_account_to_user("user1".to_string(), accounts(1));
+    assert_eq!(contract.get_user_for_account(accounts(1)), Some("user1".to_string()));
+    assert!(contract.is_account_in_municipality("municipality1".to_string(), accounts(1)));
+    assert!(!contract.is_account_in_municipality("municipality2".to_string(), accounts(1)));
+    assert!(!contract.is_account_in_municipality("municipality1".to_string(), accounts(2)));
+}
+
+#[test]
+fn test_user_not_in_other_municipality() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new(accounts(0));
+    contract.add_user_to_municipality("municipality1".to_string(), "user1".to_string());
+    contract.add_user_to_municipality("municipality2".to_string(), "user2".to_string());
+    contract.link_account_to_user("user1".to_string(), accounts(1));
+    assert!(contract.is_user_in_municipality("municipality1".to_string(), "user1".to_string()));
+    assert!(!contract.is_user_in_municipality("municipality2".to_string(), "user1".to_string()));
+    assert!(!contract.is_account_in_municipality("municipality2".to_string(), accounts(1)));
+    assert_eq!(contract.get_users_for_municipality("municipality2".to_string(), None, None), vec!["user2".to_string()]);
+}
+
+#[test]
+fn test_change_account_for_user() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new(accounts(0));
+    contract.add_user_to_municipality("municipality1".to_string(), "user1".to_string());
+    contract.link_account_to_user("user1".to_string(), accounts(1));
+    contract.link_account_to_user("user1".to_string(), accounts(2));
+    assert_eq!(contract.get_user_for_account(accounts(1)), None);
+    assert!(!contract.is_account_in_municipality("municipality1".to_string(), accounts(1)));
+    assert!(contract.is_account_in_municipality("municipality1".to_string(), accounts(2)));
+}
+
+#[test]
+#[should_panic(expected = "Account is already linked to another user")]
+fn test_link_account_to_second_user() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = Contract::new(accounts(0));
+    contract.link_account_to_user("user1".to_string(), accounts(1));
+    contract.link_account_to_user("user2".to_string(), accounts(1));
+}
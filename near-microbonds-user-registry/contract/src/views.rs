@@ -47,4 +47,18 @@ impl Contract {
         // Check if the user is in the UnorderedSet
         users.contains(&user_id)
     }
+
+    /// Gets the user the given account is linked to
+    pub fn get_user_for_account(&self, account_id: AccountId) -> Option<String> {
+        self.account_to_user.get(&account_id)
+    }
+
+    /// Returns if the user linked to the given account is in the given municipality
+    pub fn is_account_in_municipality(&self, municipality_id: String, account_id: AccountId) -> bool {
+        // Get the user linked to the account, if there is none the account isn't in any municipality
+        match self.account_to_user.get(&account_id) {
+            Some(user_id) => self.is_user_in_municipality(municipality_id, user_id),
+            None => false,
+        }
+    }
 }
\ No newline at end of file